@@ -0,0 +1,61 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::oci;
+use crate::environment::Environment;
+use crate::OracleResult;
+
+/// Service context of connection shared with cancel handles, it is detached when connection is released
+pub(crate) struct CancelTarget {
+    svchp:     Mutex<*mut oci::OCISvcCtx>,
+    // own error handle, break is called from other thread than calls of connection
+    errhp:     *mut oci::OCIError,
+    cancelled: AtomicBool,
+}
+
+// service context is guarded by mutex, OCIBreak is thread-safe
+unsafe impl Sync for CancelTarget {}
+unsafe impl Send for CancelTarget {}
+
+impl CancelTarget {
+    pub(crate) fn new(env: &'static Environment, svchp: *mut oci::OCISvcCtx) -> OracleResult<CancelTarget> {
+        let errhp = oci::handle_alloc(env.envhp, oci::OCI_HTYPE_ERROR)? as *mut oci::OCIError;
+        Ok( CancelTarget { svchp: Mutex::new(svchp), errhp, cancelled: AtomicBool::new(false) } )
+    }
+
+    /// detach from released connection, returns true if call of connection was cancelled
+    pub(crate) fn detach(&self) -> bool {
+        let mut svchp = self.svchp.lock().unwrap();
+        *svchp = std::ptr::null_mut();
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for CancelTarget {
+    fn drop(&mut self) {
+        oci::handle_free(self.errhp as *mut oci::c_void, oci::OCI_HTYPE_ERROR);
+    }
+}
+
+/// Handle to cancel current call of connection from other thread
+#[derive(Clone)]
+pub struct CancelHandle {
+    target: Arc<CancelTarget>,
+}
+
+impl CancelHandle {
+    pub(crate) fn new(target: Arc<CancelTarget>) -> CancelHandle {
+        CancelHandle { target }
+    }
+
+    /// break current call of connection, it fails with ORA-01013.
+    /// nothing happens if connection is already released
+    pub fn cancel(&self) -> OracleResult<()> {
+        let svchp = self.target.svchp.lock().unwrap();
+        if svchp.is_null() {
+            return Ok(());
+        }
+        self.target.cancelled.store(true, Ordering::Relaxed);
+        oci::break_call(*svchp, self.target.errhp)
+    }
+}
@@ -4,7 +4,12 @@
 use crate::oci;
 
 use crate::environment::Environment;
-use crate::{statement, OracleResult, SQLParams, ParamsProvider, SQLResults};
+use crate::{statement, OracleResult, OracleError, SQLParams, ParamsProvider, SQLResults};
+use crate::transaction::Transaction;
+use crate::cancel::{CancelHandle, CancelTarget};
+use crate::lob::{SqlLob, LobKind, TemporaryLob, LobReader, LobWriter};
+use std::cell::RefCell;
+use std::sync::Arc;
 
 /// Connection to Oracle and server context
 /*
@@ -25,27 +30,175 @@ pub struct SessionPool {
     poolname: String,
 }
 
+/// Statistics of session pool
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStats {
+    pub open:      u32,
+    pub busy:      u32,
+    pub min:       u32,
+    pub max:       u32,
+    pub increment: u32,
+}
+
 // for multithreading and lazy_static
 unsafe impl Sync for SessionPool {}
 unsafe impl Send for SessionPool {}
 
 /// Pooled Connection to Oracle and server context
 pub struct Connection {
-    env: &'static Environment,
+    pub(crate) env: &'static Environment,
     pub(crate) errhp: *mut oci::OCIError,
     pub(crate) svchp: *mut oci::OCISvcCtx,
+    // shared with cancel handles, it is created by first request of handle
+    cancel: RefCell<Option<Arc<CancelTarget>>>,
+}
+
+/// Mode of getting session from pool, when all sessions are busy and pool has maximal size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GetMode {
+    /// wait until session is released
+    Wait,
+    /// return error immediately
+    NoWait,
+    /// wait for milliseconds, then return error
+    TimedWait(u32)
+}
+
+/// Builder of session pool, default pool has 1..10 sessions
+pub struct SessionPoolBuilder {
+    db:       String,
+    username: String,
+    passwd:   String,
+
+    min:             u32,
+    max:             u32,
+    increment:       u32,
+    get_mode:        GetMode,
+    idle_timeout:    Option<u32>,  // seconds
+    max_lifetime:    Option<u32>,  // seconds
+    stmt_cache_size: Option<u32>,
 }
 
-/// create a session pool
+/// create a session pool with default parameters
 pub fn create_pool(db: &str, username: &str, passwd: &str) -> OracleResult<SessionPool> {
-    let env = Environment::get()?;
-    let errhp = env.errhp;
+    SessionPool::builder(db, username, passwd).build()
+}
+
+impl SessionPoolBuilder {
+    /// minimal count of sessions, they are opened on creation of pool
+    pub fn min(mut self, min: u32) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// maximal count of sessions
+    pub fn max(mut self, max: u32) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// count of sessions opened when pool grows
+    pub fn increment(mut self, increment: u32) -> Self {
+        self.increment = increment;
+        self
+    }
+
+    pub fn get_mode(mut self, get_mode: GetMode) -> Self {
+        self.get_mode = get_mode;
+        self
+    }
+
+    /// idle sessions are closed after timeout in seconds
+    pub fn idle_timeout(mut self, seconds: u32) -> Self {
+        self.idle_timeout = Some(seconds);
+        self
+    }
+
+    /// sessions are closed after lifetime in seconds
+    pub fn max_lifetime(mut self, seconds: u32) -> Self {
+        self.max_lifetime = Some(seconds);
+        self
+    }
+
+    /// count of cached statements in each session
+    pub fn stmt_cache_size(mut self, size: u32) -> Self {
+        self.stmt_cache_size = Some(size);
+        self
+    }
+
+    pub fn build(self) -> OracleResult<SessionPool> {
+        if self.min > self.max || self.max == 0 {
+            return Err(OracleError::new(format!("Invalid size of session pool: min {}, max {}", self.min, self.max), "SessionPoolBuilder::build"));
+        }
+
+        let env = Environment::get()?;
+        let errhp = env.errhp;
+
+        let (poolhp, poolname) = oci::create_session_pool(env.envhp, errhp, self.min, self.max, self.increment,
+                                                           &self.db, &self.username, &self.passwd)?;
+        // pool is destroyed on error of attributes
+        let pool = SessionPool{env, errhp, poolhp, poolname};
 
-    let (poolhp, poolname) = oci::create_session_pool(env.envhp, errhp, 1,2, db, username, passwd)?;
-    Ok(SessionPool{env, errhp, poolhp, poolname })
+        let (get_mode, wait_timeout) = match self.get_mode {
+            GetMode::Wait => (oci::OCI_SPOOL_ATTRVAL_WAIT, None),
+            GetMode::NoWait => (oci::OCI_SPOOL_ATTRVAL_NOWAIT, None),
+            GetMode::TimedWait(ms) => (oci::OCI_SPOOL_ATTRVAL_TIMEDWAIT, Some(ms))
+        };
+        pool.set_attr(oci::OCI_ATTR_SPOOL_GETMODE, get_mode)?;
+
+        let attributes = [
+            (oci::OCI_ATTR_SPOOL_WAIT_TIMEOUT, wait_timeout),
+            (oci::OCI_ATTR_SPOOL_TIMEOUT, self.idle_timeout),
+            (oci::OCI_ATTR_SPOOL_MAX_LIFETIME_SESSION, self.max_lifetime),
+            (oci::OCI_ATTR_SPOOL_STMTCACHESIZE, self.stmt_cache_size)
+        ];
+        for (attr, value) in attributes.iter() {
+            if let Some(value) = value {
+                pool.set_attr(*attr, *value)?;
+            }
+        }
+
+        Ok(pool)
+    }
 }
 
 impl SessionPool {
+    pub fn builder(db: &str, username: &str, passwd: &str) -> SessionPoolBuilder {
+        SessionPoolBuilder {
+            db: db.to_string(), username: username.to_string(), passwd: passwd.to_string(),
+            min: 1, max: 10, increment: 1, get_mode: GetMode::Wait,
+            idle_timeout: None, max_lifetime: None, stmt_cache_size: None
+        }
+    }
+
+    /// set attribute of pool, value is u8 or u32 by attribute
+    fn set_attr<T>(&self, attr: u32, value: T) -> OracleResult<()> {
+        let value_ptr = &value as *const T;
+        oci::attr_set(self.poolhp as *mut oci::c_void, oci::OCI_HTYPE_SPOOL,
+                      value_ptr as *mut oci::c_void, std::mem::size_of::<T>() as u32,
+                      attr, self.errhp as *mut oci::OCIError)
+    }
+
+    /// get attribute of pool, value is u8 or u32 by attribute
+    fn get_attr<T: Default>(&self, attr: u32) -> OracleResult<T> {
+        let mut value = T::default();
+        let value_ptr = &mut value as *mut T;
+        oci::attr_get(self.poolhp as *mut oci::c_void, oci::OCI_HTYPE_SPOOL,
+                      value_ptr as *mut oci::c_void, attr, self.errhp as *mut oci::OCIError)?;
+        Ok(value)
+    }
+
+    /// current count of open and busy sessions, and size of pool
+    pub fn stats(&self) -> OracleResult<PoolStats> {
+        Ok( PoolStats {
+            open: self.get_attr(oci::OCI_ATTR_SPOOL_OPEN_COUNT)?,
+            busy: self.get_attr(oci::OCI_ATTR_SPOOL_BUSY_COUNT)?,
+            min: self.get_attr(oci::OCI_ATTR_SPOOL_MIN)?,
+            max: self.get_attr(oci::OCI_ATTR_SPOOL_MAX)?,
+            increment: self.get_attr(oci::OCI_ATTR_SPOOL_INCR)?
+        })
+    }
+
     pub fn connect(&self) -> OracleResult<Connection> {
         let svchp = oci::session_get(self.env.envhp, self.errhp as *mut oci::OCIError, &self.poolname)?;
         Ok( Connection::new(self.env, self.errhp as *mut oci::OCIError, svchp) )
@@ -60,6 +213,14 @@ impl Drop for SessionPool {
 
 impl Drop for Connection {
     fn drop(&mut self) {
+        // session is returned to pool after reset of cancelled call
+        if let Some(target) = self.cancel.borrow_mut().take() {
+            if target.detach() {
+                if let Err(err) = oci::reset(self.svchp, self.errhp) {
+                    eprintln!("WARNING: can not reset cancelled session: {}", err);
+                }
+            }
+        }
         oci::session_release(self.svchp, self.errhp);
     }
 }
@@ -112,7 +273,12 @@ impl Connection {
            errhp: *mut oci::OCIError,
            svchp: *mut oci::OCISvcCtx) -> Connection {
         // Connection { env, srvhp, authp, errhp, svchp }
-        Connection { env, errhp, svchp }
+        Connection { env, errhp, svchp, cancel: RefCell::new(None) }
+    }
+
+    /// begin transaction, it is rolled back on drop unless committed
+    pub fn begin(&self) -> Transaction<'_> {
+        Transaction::new(self)
     }
 
     /// commit transaction with NO-WAIT option
@@ -125,8 +291,8 @@ impl Connection {
         oci::rollback(self.svchp, self.env.errhp)
     }
 
-    /// Execute generic SQL statement
-    pub fn execute<'conn,'s>(&'conn self, sql: &'s str) -> OracleResult<()> {
+    /// Execute generic SQL statement, returns count of affected rows
+    pub fn execute<'conn,'s>(&'conn self, sql: &'s str) -> OracleResult<u64> {
         let st = statement::Statement::new(self, sql, Box::new(()))?;
         st.execute(())
     }
@@ -161,6 +327,81 @@ impl Connection {
         statement::Statement::new(self, sql, provider)?.query_one()
     }
 
+    /// set timeout of each round trip to server in milliseconds, 0 disables timeout.
+    /// call which exceeds timeout fails, connection remains usable
+    pub fn set_call_timeout(&self, millis: u32) -> OracleResult<()> {
+        let value_ptr = &millis as *const u32;
+        oci::attr_set(self.svchp as *mut oci::c_void, oci::OCI_HTYPE_SVCCTX,
+                      value_ptr as *mut oci::c_void, std::mem::size_of::<u32>() as u32,
+                      oci::OCI_ATTR_CALL_TIMEOUT, self.errhp)
+    }
+
+    /// handle to cancel current call from other thread, it is valid until connection is released
+    pub fn cancel_handle(&self) -> OracleResult<CancelHandle> {
+        let mut cancel = self.cancel.borrow_mut();
+        let target = match cancel.as_ref() {
+            Some(target) => target.clone(),
+            None => {
+                let target = Arc::new(CancelTarget::new(self.env, self.svchp)?);
+                *cancel = Some(target.clone());
+                target
+            }
+        };
+        Ok( CancelHandle::new(target) )
+    }
+
+    /// create temporary LOB of session, it is freed on drop
+    pub fn create_temporary_lob(&self, kind: LobKind) -> OracleResult<TemporaryLob<'_>> {
+        TemporaryLob::new(self, kind)
+    }
+
+    /// length of LOB, in characters for CLOB and NCLOB, in bytes for BLOB and BFILE
+    pub fn lob_length(&self, lob: &SqlLob) -> OracleResult<u64> {
+        oci::lob_get_length(self.svchp, self.errhp, lob.locator)
+    }
+
+    /// read content of LOB or BFILE by chunks
+    pub fn lob_reader<'a>(&'a self, lob: &'a SqlLob) -> OracleResult<LobReader<'a>> {
+        LobReader::new(self, lob)
+    }
+
+    /// replace content of LOB by chunks, LOB must be selected FOR UPDATE or returned by DML
+    pub fn lob_writer<'a>(&'a self, lob: &'a SqlLob) -> OracleResult<LobWriter<'a>> {
+        LobWriter::new(self, lob)
+    }
+
+    /// set CLIENT_IDENTIFIER of session, e.g. name of end user
+    pub fn set_client_identifier(&self, value: &str) -> OracleResult<()> {
+        self.set_session_attr(oci::OCI_ATTR_CLIENT_IDENTIFIER, value)
+    }
+
+    /// set MODULE of session, e.g. name of application
+    pub fn set_module(&self, value: &str) -> OracleResult<()> {
+        self.set_session_attr(oci::OCI_ATTR_MODULE, value)
+    }
+
+    /// set ACTION of session, e.g. name of operation in module
+    pub fn set_action(&self, value: &str) -> OracleResult<()> {
+        self.set_session_attr(oci::OCI_ATTR_ACTION, value)
+    }
+
+    /// set CLIENT_INFO of session
+    pub fn set_client_info(&self, value: &str) -> OracleResult<()> {
+        self.set_session_attr(oci::OCI_ATTR_CLIENT_INFO, value)
+    }
+
+    /// set tracing attribute of session, it is sent to server with next round trip.
+    /// pooled session keeps attributes after release, empty value clears attribute
+    fn set_session_attr(&self, attr: u32, value: &str) -> OracleResult<()> {
+        let mut authp: *mut oci::OCISession = std::ptr::null_mut();
+        oci::attr_get(self.svchp as *mut oci::c_void, oci::OCI_HTYPE_SVCCTX,
+                      &mut authp as *mut *mut oci::OCISession as *mut oci::c_void,
+                      oci::OCI_ATTR_SESSION, self.errhp)?;
+        oci::attr_set(authp as *mut oci::c_void, oci::OCI_HTYPE_SESSION,
+                      value.as_ptr() as *mut oci::c_void, value.len() as u32,
+                      attr, self.errhp)
+    }
+
 }
 
 /*
@@ -4,24 +4,37 @@ mod connection;
 mod types;
 mod values;
 mod statement;
+mod transaction;
+mod cancel;
+mod lob;
 mod implementors;
 
 pub use oci::{OracleError, OracleResult};
-pub use connection::{Connection, SessionPool, create_pool};
+pub use connection::{Connection, SessionPool, SessionPoolBuilder, GetMode, PoolStats, create_pool};
+pub use transaction::Transaction;
+pub use cancel::CancelHandle;
+pub use lob::{SqlLob, SqlBFile, LobKind, TemporaryLob, LobReader, LobWriter};
 
 pub use types::{
     SqlType,
     SqlDate, SqlDateTime,
+    SqlTimestamp, SqlTimestampTz,
+    SqlIntervalYM, SqlIntervalDS,
+    SqlDecimal,
     TypeDescriptor,
     TypeDescriptorProducer
 };
 
 pub use statement::{
     Statement,
+    BatchResult,
+    BatchError,
     Query,
     QueryIterator,
     ResultsProvider,
     ResultSet,
+    ResultValue,
+    OutValues,
     ParamsProvider,
     ParamsProjection,
     ParamValue,
@@ -30,6 +43,7 @@ pub use statement::{
 };
 
 pub use statement::params::{
+    Direction,
     Identifier,
     Member,
     ValueProjector
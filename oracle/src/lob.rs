@@ -0,0 +1,232 @@
+use std::io;
+use std::ops::Deref;
+
+use crate::oci;
+use crate::connection::Connection;
+use crate::environment::Environment;
+use crate::{OracleResult, OracleError};
+
+/// Type of temporary LOB
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LobKind {
+    Blob, Clob, NClob
+}
+
+/// Locator of LOB (BLOB, CLOB, NCLOB) or BFILE, it is own copy of fetched locator.
+/// content is read and written through connection: `conn.lob_reader(&lob)?`
+pub struct SqlLob {
+    pub(crate) locator: *mut oci::OCILobLocator,
+    dtype:              u32,
+}
+
+/// Locator of BFILE, it is defined by its own type and read as LOB
+pub struct SqlBFile(SqlLob);
+
+impl SqlLob {
+    /// allocate empty locator of LOB (OCI_DTYPE_LOB) or BFILE (OCI_DTYPE_FILE)
+    pub(crate) fn alloc(dtype: u32) -> OracleResult<SqlLob> {
+        let env = Environment::get()?;
+        let locator = oci::descriptor_alloc(env.envhp, dtype)? as *mut oci::OCILobLocator;
+        Ok( SqlLob { locator, dtype } )
+    }
+
+    /// copy of locator from buffer of row, it remains valid after next fetch
+    pub(crate) fn copy_of(src: *const oci::OCILobLocator, dtype: u32) -> OracleResult<SqlLob> {
+        let env = Environment::get()?;
+        let lob = SqlLob::alloc(dtype)?;
+        oci::lob_assign(env.envhp, env.errhp, src, lob.locator)?;
+        Ok(lob)
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.dtype == oci::OCI_DTYPE_FILE
+    }
+
+    /// CLOB or NCLOB, content is text in client character set
+    pub fn is_character(&self) -> OracleResult<bool> {
+        Ok(self.charset_form()? != 0)
+    }
+
+    /// SQLCS_IMPLICIT or SQLCS_NCHAR for character LOB, 0 for BLOB and BFILE
+    pub(crate) fn charset_form(&self) -> OracleResult<u8> {
+        if self.is_file() {
+            return Ok(0);
+        }
+        let env = Environment::get()?;
+        oci::lob_charset_form(env.envhp, env.errhp, self.locator)
+    }
+}
+
+impl Drop for SqlLob {
+    fn drop(&mut self) {
+        oci::descriptor_free(self.locator as *mut oci::c_void, self.dtype);
+    }
+}
+
+impl SqlBFile {
+    pub(crate) fn new(lob: SqlLob) -> SqlBFile {
+        SqlBFile(lob)
+    }
+}
+
+impl Deref for SqlBFile {
+    type Target = SqlLob;
+
+    fn deref(&self) -> &SqlLob {
+        &self.0
+    }
+}
+
+impl From<SqlBFile> for SqlLob {
+    fn from(file: SqlBFile) -> SqlLob {
+        file.0
+    }
+}
+
+/// Temporary LOB of session, it is freed on drop.
+/// it is written like persistent LOB and binded as parameter (it derefs to SqlLob)
+pub struct TemporaryLob<'conn> {
+    conn: &'conn Connection,
+    lob:  SqlLob,
+}
+
+impl <'conn> TemporaryLob<'conn> {
+    pub(crate) fn new(conn: &'conn Connection, kind: LobKind) -> OracleResult<TemporaryLob<'conn>> {
+        let (lobtype, csfrm) = match kind {
+            LobKind::Blob => (oci::OCI_TEMP_BLOB, oci::SQLCS_IMPLICIT),
+            LobKind::Clob => (oci::OCI_TEMP_CLOB, oci::SQLCS_IMPLICIT),
+            LobKind::NClob => (oci::OCI_TEMP_CLOB, oci::SQLCS_NCHAR),
+        };
+        let lob = SqlLob::alloc(oci::OCI_DTYPE_LOB)?;
+        oci::lob_create_temporary(conn.svchp, conn.errhp, lob.locator, lobtype, csfrm)?;
+        Ok( TemporaryLob { conn, lob } )
+    }
+}
+
+impl Deref for TemporaryLob<'_> {
+    type Target = SqlLob;
+
+    fn deref(&self) -> &SqlLob {
+        &self.lob
+    }
+}
+
+impl Drop for TemporaryLob<'_> {
+    fn drop(&mut self) {
+        if let Err(err) = oci::lob_free_temporary(self.conn.svchp, self.conn.errhp, self.lob.locator) {
+            eprintln!("WARNING: can not free temporary LOB: {}", err);
+        }
+    }
+}
+
+/// Sequential reader of LOB content by chunks, each chunk is one round trip.
+/// character LOB is read as text in client character set, BFILE is opened while reading
+pub struct LobReader<'a> {
+    conn:   &'a Connection,
+    lob:    &'a SqlLob,
+    csfrm:  u8,
+    offset: u64,    // from 1, in characters for CLOB and NCLOB
+    done:   bool,
+}
+
+impl <'a> LobReader<'a> {
+    pub(crate) fn new(conn: &'a Connection, lob: &'a SqlLob) -> OracleResult<LobReader<'a>> {
+        let csfrm = lob.charset_form()?;
+        if lob.is_file() {
+            oci::lob_file_open(conn.svchp, conn.errhp, lob.locator)?;
+        }
+        Ok( LobReader { conn, lob, csfrm, offset: 1, done: false } )
+    }
+
+    /// read next chunk to buffer, returns 0 at end of LOB.
+    /// buffer for character LOB must hold at least one character (4 bytes)
+    pub fn read_chunk(&mut self, buf: &mut [u8]) -> OracleResult<usize> {
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let (bytes, chars) = oci::lob_read(self.conn.svchp, self.conn.errhp, self.lob.locator, self.offset, buf, self.csfrm)?;
+        if bytes == 0 {
+            self.done = true;
+        }
+        self.offset += if self.csfrm == 0 { bytes } else { chars };
+        Ok(bytes as usize)
+    }
+}
+
+impl io::Read for LobReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_chunk(buf).map_err(io::Error::other)
+    }
+}
+
+impl Drop for LobReader<'_> {
+    fn drop(&mut self) {
+        if self.lob.is_file() {
+            if let Err(err) = oci::lob_file_close(self.conn.svchp, self.conn.errhp, self.lob.locator) {
+                eprintln!("WARNING: can not close BFILE: {}", err);
+            }
+        }
+    }
+}
+
+/// Sequential writer of LOB content by chunks, it replaces content of LOB (LOB is trimmed on creation).
+/// character LOB is written as UTF-8 text, character split between chunks is kept until next chunk
+pub struct LobWriter<'a> {
+    conn:    &'a Connection,
+    lob:     &'a SqlLob,
+    csfrm:   u8,
+    offset:  u64,       // from 1, in characters for CLOB and NCLOB
+    pending: Vec<u8>,   // incomplete UTF-8 character at end of last chunk
+}
+
+impl <'a> LobWriter<'a> {
+    pub(crate) fn new(conn: &'a Connection, lob: &'a SqlLob) -> OracleResult<LobWriter<'a>> {
+        if lob.is_file() {
+            return Err(OracleError::new("BFILE is read only".to_string(), "LobWriter::new"));
+        }
+        let csfrm = lob.charset_form()?;
+        oci::lob_trim(conn.svchp, conn.errhp, lob.locator, 0)?;
+        Ok( LobWriter { conn, lob, csfrm, offset: 1, pending: Vec::new() } )
+    }
+
+    /// append chunk to LOB, one round trip for chunk
+    pub fn write_chunk(&mut self, data: &[u8]) -> OracleResult<()> {
+        if self.csfrm == 0 {
+            self.offset += oci::lob_write(self.conn.svchp, self.conn.errhp, self.lob.locator, self.offset, data, self.csfrm)?;
+            return Ok(());
+        }
+
+        self.pending.extend_from_slice(data);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => return Err(OracleError::new("Invalid UTF-8 text for character LOB".to_string(), "LobWriter::write_chunk"))
+        };
+        if valid > 0 {
+            self.offset += oci::lob_write(self.conn.svchp, self.conn.errhp, self.lob.locator, self.offset, &self.pending[..valid], self.csfrm)?;
+            self.pending.drain(..valid);
+        }
+        Ok(())
+    }
+
+    /// check that text is complete, returns length of LOB in characters (bytes for BLOB)
+    pub fn finish(self) -> OracleResult<u64> {
+        if !self.pending.is_empty() {
+            return Err(OracleError::new("Incomplete UTF-8 character at end of character LOB".to_string(), "LobWriter::finish"));
+        }
+        Ok(self.offset - 1)
+    }
+}
+
+impl io::Write for LobWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_chunk(buf)
+            .map(|_|buf.len())
+            .map_err(io::Error::other)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -72,6 +72,25 @@ pub struct OCIAuthInfo {
     _unused: [u8; 0],
 }
 
+// datetime and interval descriptors
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct OCIDateTime {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct OCIInterval {
+    _unused: [u8; 0],
+}
+
+// locator of LOB or BFILE
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct OCILobLocator {
+    _unused: [u8; 0],
+}
+
 // OCI functions
 
 extern "C" {
@@ -258,6 +277,13 @@ extern "C" {
     ) -> c_int;
 }
 
+extern "C" {
+    pub fn OCIBreak(hndlp: *mut c_void, errhp: *mut OCIError) -> c_int;
+}
+extern "C" {
+    pub fn OCIReset(hndlp: *mut c_void, errhp: *mut OCIError) -> c_int;
+}
+
 extern "C" {
     pub fn OCITransCommit(svchp: *mut OCISvcCtx, errhp: *mut OCIError, flags: c_uint) -> c_int;
 }
@@ -381,3 +407,227 @@ extern "C" {
         mode: c_uint,
     ) -> c_int;
 }
+
+extern "C" {
+    pub fn OCIParamGet(
+        hndlp: *const c_void,
+        htype: c_uint,
+        errhp: *mut OCIError,
+        parmdpp: *mut *mut c_void,
+        pos: c_uint,
+    ) -> c_int;
+}
+
+extern "C" {
+    pub fn OCIDescriptorAlloc(
+        parenth: *const c_void,
+        descpp: *mut *mut c_void,
+        dtype: c_uint,
+        xtramem_sz: usize,
+        usrmempp: *mut *mut c_void,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCIDescriptorFree(descp: *mut c_void, dtype: c_uint) -> c_int;
+}
+
+extern "C" {
+    pub fn OCIDateTimeGetDate(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        date: *const OCIDateTime,
+        yr: *mut i16,
+        mnth: *mut u8,
+        dy: *mut u8,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCIDateTimeGetTime(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        datetime: *mut OCIDateTime,
+        hr: *mut u8,
+        mm: *mut u8,
+        ss: *mut u8,
+        fsec: *mut u32,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCIDateTimeGetTimeZoneOffset(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        datetime: *const OCIDateTime,
+        hr: *mut i8,
+        mm: *mut i8,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCIDateTimeConstruct(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        datetime: *mut OCIDateTime,
+        yr: i16,
+        mnth: u8,
+        dy: u8,
+        hr: u8,
+        mm: u8,
+        ss: u8,
+        fsec: u32,
+        timezone: *const c_uchar,
+        timezone_length: usize,
+    ) -> c_int;
+}
+
+extern "C" {
+    pub fn OCIIntervalGetYearMonth(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        yr: *mut i32,
+        mnth: *mut i32,
+        result: *const OCIInterval,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCIIntervalSetYearMonth(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        yr: i32,
+        mnth: i32,
+        result: *mut OCIInterval,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCIIntervalGetDaySecond(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        dy: *mut i32,
+        hr: *mut i32,
+        mm: *mut i32,
+        ss: *mut i32,
+        fsec: *mut i32,
+        result: *const OCIInterval,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCIIntervalSetDaySecond(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        dy: i32,
+        hr: i32,
+        mm: i32,
+        ss: i32,
+        fsec: i32,
+        result: *mut OCIInterval,
+    ) -> c_int;
+}
+
+extern "C" {
+    pub fn OCILobAssign(
+        envhp: *mut OCIEnv,
+        errhp: *mut OCIError,
+        src_locp: *const OCILobLocator,
+        dst_locpp: *mut *mut OCILobLocator,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobCharSetForm(
+        envhp: *mut OCIEnv,
+        errhp: *mut OCIError,
+        locp: *const OCILobLocator,
+        csfrm: *mut u8,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobGetLength2(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+        lenp: *mut u64,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobRead2(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+        byte_amtp: *mut u64,
+        char_amtp: *mut u64,
+        offset: u64,
+        bufp: *mut c_void,
+        bufl: u64,
+        piece: u8,
+        ctxp: *mut c_void,
+        cbfp: *mut c_void,
+        csid: u16,
+        csfrm: u8,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobWrite2(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+        byte_amtp: *mut u64,
+        char_amtp: *mut u64,
+        offset: u64,
+        bufp: *mut c_void,
+        buflen: u64,
+        piece: u8,
+        ctxp: *mut c_void,
+        cbfp: *mut c_void,
+        csid: u16,
+        csfrm: u8,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobTrim2(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+        newlen: u64,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobCreateTemporary(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+        csid: u16,
+        csfrm: u8,
+        lobtype: u8,
+        cache: c_int,
+        duration: u16,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobFreeTemporary(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobFileOpen(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        filep: *mut OCILobLocator,
+        mode: u8,
+    ) -> c_int;
+}
+extern "C" {
+    pub fn OCILobFileClose(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        filep: *mut OCILobLocator,
+    ) -> c_int;
+}
+
+extern "C" {
+    pub fn OCIStmtGetNextResult(
+        stmthp: *mut OCIStmt,
+        errhp: *mut OCIError,
+        result: *mut *mut c_void,
+        rtype: *mut c_uint,
+        mode: c_uint,
+    ) -> c_int;
+}
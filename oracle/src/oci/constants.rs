@@ -19,6 +19,24 @@ pub const OCI_HTYPE_BIND: u32 = 5;
 pub const OCI_HTYPE_DEFINE: u32 = 6;
 pub const OCI_HTYPE_SPOOL: u32 = 27;
 
+// descriptors
+pub const OCI_DTYPE_INTERVAL_YM: u32 = 62;
+pub const OCI_DTYPE_INTERVAL_DS: u32 = 63;
+pub const OCI_DTYPE_TIMESTAMP: u32 = 68;
+pub const OCI_DTYPE_TIMESTAMP_TZ: u32 = 69;
+pub const OCI_DTYPE_TIMESTAMP_LTZ: u32 = 70;
+pub const OCI_DTYPE_LOB: u32 = 50;
+pub const OCI_DTYPE_FILE: u32 = 56;
+
+// LOB
+pub const OCI_ONE_PIECE: u8 = 0;
+pub const OCI_TEMP_BLOB: u8 = 1;
+pub const OCI_TEMP_CLOB: u8 = 2;
+pub const OCI_DURATION_SESSION: u16 = 10;
+pub const OCI_FILE_READONLY: u8 = 1;
+pub const SQLCS_IMPLICIT: u8 = 1;   /* database character set */
+pub const SQLCS_NCHAR: u8 = 2;      /* national character set */
+
 // ERROR CODES
 pub const OCI_SUCCESS: i32 = 0;
 pub const OCI_SUCCESS_WITH_INFO: i32 = 1;
@@ -38,9 +56,13 @@ pub const OCI_DESCRIBE_ONLY: u32 = 16;
 pub const OCI_COMMIT_ON_SUCCESS: u32 = 32;
 pub const OCI_NON_BLOCKING: u32 = 64;
 pub const OCI_BATCH_ERRORS: u32 = 128;
+pub const OCI_RETURN_ROW_COUNT_ARRAY: u32 = 0x00100000;
 pub const OCI_PARSE_ONLY: u32 = 256;
 pub const OCI_NTV_SYNTAX: u32 = 1;
 
+// statement types
+pub const OCI_STMT_SELECT: u16 = 1;
+
 pub const OCI_PARAM_IN: u32 = 1;
 pub const OCI_PARAM_OUT: u32 = 2;
 
@@ -51,7 +73,20 @@ pub const OCI_ATTR_TRANS: u32 = 8;
 pub const OCI_ATTR_USERNAME: u32 = 22;
 pub const OCI_ATTR_PASSWORD: u32 = 23;
 pub const OCI_ATTR_PREFETCH_ROWS: u32 = 11;   /* sets the number of rows to prefetch */
+pub const OCI_ATTR_ROW_COUNT: u32 = 9;
+pub const OCI_ATTR_STMT_TYPE: u32 = 24;
+pub const OCI_ATTR_UB8_ROW_COUNT: u32 = 457;  /* row count as ub8, ub4 row count overflows */
 pub const OCI_ATTR_ROWS_FETCHED: u32 = 197;
+pub const OCI_ATTR_NUM_DML_ERRORS: u32 = 73;
+pub const OCI_ATTR_DML_ROW_OFFSET: u32 = 74;
+pub const OCI_ATTR_DML_ROW_COUNT_ARRAY: u32 = 469;
+pub const OCI_ATTR_CALL_TIMEOUT: u32 = 531;   /* timeout of round trip in milliseconds */
+
+// end-to-end tracing attributes of session, they are sent with next round trip
+pub const OCI_ATTR_CLIENT_IDENTIFIER: u32 = 278;
+pub const OCI_ATTR_MODULE: u32 = 366;
+pub const OCI_ATTR_ACTION: u32 = 367;
+pub const OCI_ATTR_CLIENT_INFO: u32 = 368;
 
 // transactions
 pub const OCI_TRANS_NEW: u32 = 1;
@@ -65,6 +100,9 @@ pub const OCI_TRANS_TWOPHASE: u32 = 16777216;
 pub const OCI_TRANS_WRITEBATCH: u32 = 1;
 pub const OCI_TRANS_WRITENOWAIT: u32 = 8;
 
+// type of implicit result
+pub const OCI_RESULT_TYPE_SELECT: u32 = 1;
+
 // Fetch direction, must be u16
 pub const OCI_FETCH_CURRENT: u16 = 1;
 pub const OCI_FETCH_NEXT: u16 = 2;
@@ -85,3 +123,21 @@ pub const OCI_SPC_STMTCACHE: u32 = 4;
 pub const OCI_SPC_NO_RLB: u32 = 8;
 pub const OCI_SESSGET_SPOOL: u32 = 1;
 pub const OCI_SESSGET_STMTCACHE: u32 = 4;
+
+// Session pool attributes
+pub const OCI_ATTR_SPOOL_STMTCACHESIZE: u32 = 208;
+pub const OCI_ATTR_SPOOL_TIMEOUT: u32 = 308;
+pub const OCI_ATTR_SPOOL_GETMODE: u32 = 309;
+pub const OCI_ATTR_SPOOL_BUSY_COUNT: u32 = 310;
+pub const OCI_ATTR_SPOOL_OPEN_COUNT: u32 = 311;
+pub const OCI_ATTR_SPOOL_MIN: u32 = 312;
+pub const OCI_ATTR_SPOOL_MAX: u32 = 313;
+pub const OCI_ATTR_SPOOL_INCR: u32 = 314;
+pub const OCI_ATTR_SPOOL_MAX_LIFETIME_SESSION: u32 = 490;
+pub const OCI_ATTR_SPOOL_WAIT_TIMEOUT: u32 = 506;
+
+// Session pool get modes, must be u8
+pub const OCI_SPOOL_ATTRVAL_WAIT: u8 = 0;
+pub const OCI_SPOOL_ATTRVAL_NOWAIT: u8 = 1;
+pub const OCI_SPOOL_ATTRVAL_FORCEGET: u8 = 2;
+pub const OCI_SPOOL_ATTRVAL_TIMEDWAIT: u8 = 3;
@@ -47,7 +47,7 @@ impl error::Error for OracleError {
 
 /// Returns an error message in the buffer provided and an ORACLE error
 #[inline]
-fn error_get(errhp: *mut OCIError, location: &'static str) -> OracleError {
+pub(crate) fn error_get(errhp: *mut OCIError, location: &'static str) -> OracleError {
     let errc: *mut i32 = &mut 0;
 
     let mut buf: Vec<u8> = Vec::with_capacity(4096);
@@ -5,6 +5,7 @@ use super::bindings::*;
 use super::constants::*;
 use super::error::{
     check_error,
+    error_get,
     OracleError
 };
 use crate::OracleResult;
@@ -61,6 +62,177 @@ pub fn handle_free(handle: *mut c_void, htype: u32) {
         unsafe { OCIHandleFree(handle, htype) }, None, "oci::handle_free").unwrap();
 }
 
+/// allocate descriptor of datetime or interval
+#[inline]
+pub fn descriptor_alloc(envhp: *mut OCIEnv, dtype: u32) -> Result<*mut c_void, OracleError> {
+    let mut descriptor = ptr::null_mut();
+    check_error(
+        unsafe {
+            OCIDescriptorAlloc(envhp as *const _, &mut descriptor, dtype, 0, ptr::null_mut())
+        }, None, "oci::descriptor_alloc").map(|_| descriptor)
+}
+
+#[inline]
+pub fn descriptor_free(descriptor: *mut c_void, dtype: u32) {
+    check_error(
+        unsafe { OCIDescriptorFree(descriptor, dtype) }, None, "oci::descriptor_free").unwrap();
+}
+
+/// date and time of datetime descriptor: year, month, day, hour, minute, second, nanoseconds
+pub fn datetime_get(envhp: *mut OCIEnv, errhp: *mut OCIError, datetime: *mut OCIDateTime)
+                    -> Result<(i16, u8, u8, u8, u8, u8, u32), OracleError> {
+    let (mut year, mut month, mut day) = (0i16, 0u8, 0u8);
+    let (mut hour, mut minute, mut second, mut nanos) = (0u8, 0u8, 0u8, 0u32);
+
+    check_error(
+        unsafe {
+            OCIDateTimeGetDate(envhp as *mut c_void, errhp, datetime, &mut year, &mut month, &mut day)
+        }, Some(errhp), "oci::datetime_get")?;
+    check_error(
+        unsafe {
+            OCIDateTimeGetTime(envhp as *mut c_void, errhp, datetime, &mut hour, &mut minute, &mut second, &mut nanos)
+        }, Some(errhp), "oci::datetime_get")?;
+
+    Ok((year, month, day, hour, minute, second, nanos))
+}
+
+/// time zone offset of datetime descriptor: hours and minutes with the same sign
+pub fn datetime_get_offset(envhp: *mut OCIEnv, errhp: *mut OCIError, datetime: *mut OCIDateTime) -> Result<(i8, i8), OracleError> {
+    let (mut hours, mut minutes) = (0i8, 0i8);
+    check_error(
+        unsafe {
+            OCIDateTimeGetTimeZoneOffset(envhp as *mut c_void, errhp, datetime, &mut hours, &mut minutes)
+        }, Some(errhp), "oci::datetime_get_offset").map(|_| (hours, minutes))
+}
+
+/// set value of datetime descriptor, time zone is like `+02:00`, it is ignored for TIMESTAMP
+pub fn datetime_construct(envhp: *mut OCIEnv, errhp: *mut OCIError, datetime: *mut OCIDateTime,
+                          value: (i16, u8, u8, u8, u8, u8, u32), timezone: Option<&str>) -> Result<(), OracleError> {
+    let (year, month, day, hour, minute, second, nanos) = value;
+    let (tz_p, tz_len) = match timezone {
+        Some(tz) => (tz.as_ptr(), tz.len()),
+        None => (ptr::null(), 0)
+    };
+    check_error(
+        unsafe {
+            OCIDateTimeConstruct(envhp as *mut c_void, errhp, datetime, year, month, day, hour, minute, second, nanos, tz_p, tz_len)
+        }, Some(errhp), "oci::datetime_construct")
+}
+
+/// years and months of interval descriptor
+pub fn interval_get_year_month(envhp: *mut OCIEnv, errhp: *mut OCIError, interval: *mut OCIInterval) -> Result<(i32, i32), OracleError> {
+    let (mut years, mut months) = (0, 0);
+    check_error(
+        unsafe {
+            OCIIntervalGetYearMonth(envhp as *mut c_void, errhp, &mut years, &mut months, interval)
+        }, Some(errhp), "oci::interval_get_year_month").map(|_| (years, months))
+}
+
+pub fn interval_set_year_month(envhp: *mut OCIEnv, errhp: *mut OCIError, interval: *mut OCIInterval, years: i32, months: i32) -> Result<(), OracleError> {
+    check_error(
+        unsafe {
+            OCIIntervalSetYearMonth(envhp as *mut c_void, errhp, years, months, interval)
+        }, Some(errhp), "oci::interval_set_year_month")
+}
+
+/// days, hours, minutes, seconds and nanoseconds of interval descriptor, all with the same sign
+pub fn interval_get_day_second(envhp: *mut OCIEnv, errhp: *mut OCIError, interval: *mut OCIInterval)
+                               -> Result<(i32, i32, i32, i32, i32), OracleError> {
+    let (mut days, mut hours, mut minutes, mut seconds, mut nanos) = (0, 0, 0, 0, 0);
+    check_error(
+        unsafe {
+            OCIIntervalGetDaySecond(envhp as *mut c_void, errhp, &mut days, &mut hours, &mut minutes, &mut seconds, &mut nanos, interval)
+        }, Some(errhp), "oci::interval_get_day_second").map(|_| (days, hours, minutes, seconds, nanos))
+}
+
+pub fn interval_set_day_second(envhp: *mut OCIEnv, errhp: *mut OCIError, interval: *mut OCIInterval,
+                               value: (i32, i32, i32, i32, i32)) -> Result<(), OracleError> {
+    let (days, hours, minutes, seconds, nanos) = value;
+    check_error(
+        unsafe {
+            OCIIntervalSetDaySecond(envhp as *mut c_void, errhp, days, hours, minutes, seconds, nanos, interval)
+        }, Some(errhp), "oci::interval_set_day_second")
+}
+
+/// copy locator of persistent LOB or BFILE to allocated descriptor
+pub fn lob_assign(envhp: *mut OCIEnv, errhp: *mut OCIError, src: *const OCILobLocator, dst: *mut OCILobLocator) -> Result<(), OracleError> {
+    let mut dst = dst;
+    check_error(
+        unsafe { OCILobAssign(envhp, errhp, src, &mut dst) }, Some(errhp), "oci::lob_assign")
+}
+
+/// character set form of LOB: SQLCS_IMPLICIT for CLOB, SQLCS_NCHAR for NCLOB, 0 for BLOB and BFILE
+pub fn lob_charset_form(envhp: *mut OCIEnv, errhp: *mut OCIError, locp: *const OCILobLocator) -> Result<u8, OracleError> {
+    let mut csfrm = 0u8;
+    check_error(
+        unsafe { OCILobCharSetForm(envhp, errhp, locp, &mut csfrm) }, Some(errhp), "oci::lob_charset_form").map(|_| csfrm)
+}
+
+/// length of LOB, in characters for CLOB and NCLOB, in bytes for BLOB and BFILE
+pub fn lob_get_length(svchp: *mut OCISvcCtx, errhp: *mut OCIError, locp: *mut OCILobLocator) -> Result<u64, OracleError> {
+    let mut len = 0u64;
+    check_error(
+        unsafe { OCILobGetLength2(svchp, errhp, locp, &mut len) }, Some(errhp), "oci::lob_get_length").map(|_| len)
+}
+
+/// read LOB from offset (from 1) to buffer in one piece, returns count of bytes and characters.
+/// character LOB is read in client character set, end of LOB is zero bytes
+pub fn lob_read(svchp: *mut OCISvcCtx, errhp: *mut OCIError, locp: *mut OCILobLocator, offset: u64, buf: &mut [u8], csfrm: u8)
+                -> Result<(u64, u64), OracleError> {
+    let (mut byte_amt, mut char_amt) = (buf.len() as u64, 0u64);
+    let status = unsafe {
+        OCILobRead2(svchp, errhp, locp, &mut byte_amt, &mut char_amt, offset,
+                    buf.as_mut_ptr() as *mut c_void, buf.len() as u64, OCI_ONE_PIECE,
+                    null_mut(), null_mut(), 0, csfrm)
+    };
+    if status == OCI_NO_DATA {
+        return Ok((0, 0));
+    }
+    check_error(status, Some(errhp), "oci::lob_read").map(|_| (byte_amt, char_amt))
+}
+
+/// write buffer to LOB from offset (from 1) in one piece, returns count of written characters (bytes for BLOB).
+/// character LOB is written in client character set, buffer must contain whole characters
+pub fn lob_write(svchp: *mut OCISvcCtx, errhp: *mut OCIError, locp: *mut OCILobLocator, offset: u64, buf: &[u8], csfrm: u8)
+                 -> Result<u64, OracleError> {
+    let (mut byte_amt, mut char_amt) = (buf.len() as u64, 0u64);
+    check_error(
+        unsafe {
+            OCILobWrite2(svchp, errhp, locp, &mut byte_amt, &mut char_amt, offset,
+                         buf.as_ptr() as *mut c_void, buf.len() as u64, OCI_ONE_PIECE,
+                         null_mut(), null_mut(), 0, csfrm)
+        }, Some(errhp), "oci::lob_write").map(|_| if csfrm == 0 { byte_amt } else { char_amt })
+}
+
+pub fn lob_trim(svchp: *mut OCISvcCtx, errhp: *mut OCIError, locp: *mut OCILobLocator, len: u64) -> Result<(), OracleError> {
+    check_error(
+        unsafe { OCILobTrim2(svchp, errhp, locp, len) }, Some(errhp), "oci::lob_trim")
+}
+
+/// create temporary LOB (OCI_TEMP_BLOB or OCI_TEMP_CLOB) in allocated locator, it lives until end of session
+pub fn lob_create_temporary(svchp: *mut OCISvcCtx, errhp: *mut OCIError, locp: *mut OCILobLocator, lobtype: u8, csfrm: u8)
+                            -> Result<(), OracleError> {
+    check_error(
+        unsafe { OCILobCreateTemporary(svchp, errhp, locp, 0, csfrm, lobtype, 1, OCI_DURATION_SESSION) },
+        Some(errhp), "oci::lob_create_temporary")
+}
+
+pub fn lob_free_temporary(svchp: *mut OCISvcCtx, errhp: *mut OCIError, locp: *mut OCILobLocator) -> Result<(), OracleError> {
+    check_error(
+        unsafe { OCILobFreeTemporary(svchp, errhp, locp) }, Some(errhp), "oci::lob_free_temporary")
+}
+
+/// open BFILE for reading, file must be closed
+pub fn lob_file_open(svchp: *mut OCISvcCtx, errhp: *mut OCIError, filep: *mut OCILobLocator) -> Result<(), OracleError> {
+    check_error(
+        unsafe { OCILobFileOpen(svchp, errhp, filep, OCI_FILE_READONLY) }, Some(errhp), "oci::lob_file_open")
+}
+
+pub fn lob_file_close(svchp: *mut OCISvcCtx, errhp: *mut OCIError, filep: *mut OCILobLocator) -> Result<(), OracleError> {
+    check_error(
+        unsafe { OCILobFileClose(svchp, errhp, filep) }, Some(errhp), "oci::lob_file_close")
+}
+
 /// used to get a particular attribute of a handle
 #[inline]
 pub fn attr_get(handle: *mut c_void,
@@ -154,7 +326,7 @@ pub fn prepare_auth(envhp: *mut OCIEnv, errhp: *mut OCIError, username: &str, pa
 }
 
 /// allocate a session pool handle & create a pool session
-pub fn create_session_pool(envhp: *mut OCIEnv, errhp: *mut OCIError, sess_min: u32, sess_max: u32, db: &str, username: &str, passwd: &str) -> OracleResult<(*mut OCISPool,String)> {
+pub fn create_session_pool(envhp: *mut OCIEnv, errhp: *mut OCIError, sess_min: u32, sess_max: u32, sess_incr: u32, db: &str, username: &str, passwd: &str) -> OracleResult<(*mut OCISPool,String)> {
     let poolhp = handle_alloc(envhp, OCI_HTYPE_SPOOL)? as *mut OCISPool;
 
     let db_len = db.len() as u32;
@@ -172,7 +344,7 @@ pub fn create_session_pool(envhp: *mut OCIEnv, errhp: *mut OCIError, sess_min: u
     let result = check_error(
         unsafe {
             OCISessionPoolCreate(envhp, errhp, poolhp, &poolname as *const *mut u8 as *mut *mut u8, &mut poolname_len,
-                                 db.as_ptr() as *mut u8, db_len, sess_min, sess_max, 1,
+                                 db.as_ptr() as *mut u8, db_len, sess_min, sess_max, sess_incr,
                                  username.as_ptr() as *mut u8, username_len, passwd.as_ptr() as *mut u8, passwd_len, OCI_SPC_STMTCACHE | OCI_SPC_HOMOGENEOUS)
         }, Some(errhp), "oci::prepare_session_pool");
 
@@ -233,6 +405,24 @@ pub fn session_release(svchp: *mut OCISvcCtx, errhp: *mut OCIError) {
 }
 
 
+/// break current call of service context, it can be called from other thread
+#[inline]
+pub fn break_call(svchp: *mut OCISvcCtx, errhp: *mut OCIError) -> Result<(), OracleError> {
+    check_error(
+        unsafe {
+            OCIBreak(svchp as *mut c_void, errhp)
+        }, Some(errhp), "oci::break_call")
+}
+
+/// reset protocol of service context after break
+#[inline]
+pub fn reset(svchp: *mut OCISvcCtx, errhp: *mut OCIError) -> Result<(), OracleError> {
+    check_error(
+        unsafe {
+            OCIReset(svchp as *mut c_void, errhp)
+        }, Some(errhp), "oci::reset")
+}
+
 /// commit transaction in write nowait mode
 #[inline]
 pub fn commit(svchp: *mut OCISvcCtx, errhp: *mut OCIError) -> Result<(), OracleError> {
@@ -296,6 +486,86 @@ pub fn stmt_execute(svchp: *mut OCISvcCtx, stmthp: *mut OCIStmt, errhp: *mut OCI
     }
 }
 
+/// count of rows processed by last execution of statement
+#[inline]
+pub fn stmt_row_count(stmthp: *mut OCIStmt, errhp: *mut OCIError) -> OracleResult<u64> {
+    let mut row_count: u64 = 0;
+    attr_get(stmthp as *mut c_void, OCI_HTYPE_STMT, &mut row_count as *mut u64 as *mut c_void, OCI_ATTR_UB8_ROW_COUNT, errhp)
+        .map(|_|row_count)
+}
+
+/// returns true if prepared statement is a query
+#[inline]
+pub fn stmt_is_query(stmthp: *mut OCIStmt, errhp: *mut OCIError) -> OracleResult<bool> {
+    let mut stmt_type: u16 = 0;
+    attr_get(stmthp as *mut c_void, OCI_HTYPE_STMT, &mut stmt_type as *mut u16 as *mut c_void, OCI_ATTR_STMT_TYPE, errhp)
+        .map(|_|stmt_type == OCI_STMT_SELECT)
+}
+
+/// executes DML statement for `iters` rows of binds in batch error mode,
+/// errors of rows are returned by batch_errors, affected rows by batch_row_counts
+#[inline]
+pub fn stmt_execute_batch(svchp: *mut OCISvcCtx, stmthp: *mut OCIStmt, errhp: *mut OCIError, iters: u32) -> OracleResult<()> {
+    let error_code = unsafe {
+        OCIStmtExecute(svchp, stmthp, errhp, iters, 0, ptr::null(), ptr::null_mut(), OCI_BATCH_ERRORS | OCI_RETURN_ROW_COUNT_ARRAY)
+    };
+    if error_code == OCI_SUCCESS_WITH_INFO {
+        return Ok(());
+    }
+    // ORA-24381 error(s) in array DML: errors of rows are collected by batch_errors
+    match check_error(error_code, Some(errhp), "oci::stmt_execute_batch") {
+        Err(err) if err.errcode == 24381 => Ok(()),
+        result => result
+    }
+}
+
+/// errors of rows after batch execution: offset of row and error
+pub fn batch_errors(envhp: *mut OCIEnv, stmthp: *mut OCIStmt, errhp: *mut OCIError) -> OracleResult<Vec<(u32, OracleError)>> {
+    let mut num_errors: u32 = 0;
+    attr_get(stmthp as *mut c_void, OCI_HTYPE_STMT, &mut num_errors as *mut u32 as *mut c_void, OCI_ATTR_NUM_DML_ERRORS, errhp)?;
+
+    if num_errors == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut row_errhp = handle_alloc(envhp, OCI_HTYPE_ERROR)?;
+    let mut errors = Vec::with_capacity(num_errors as usize);
+
+    for i in 0..num_errors {
+        let fetched = check_error(
+            unsafe {
+                OCIParamGet(errhp as *const c_void, OCI_HTYPE_ERROR, errhp, &mut row_errhp, i)
+            }, Some(errhp), "oci::batch_errors")
+            .and_then(|_| {
+                let mut row_offset: u32 = 0;
+                attr_get(row_errhp, OCI_HTYPE_ERROR, &mut row_offset as *mut u32 as *mut c_void, OCI_ATTR_DML_ROW_OFFSET, errhp)
+                    .map(|_|row_offset)
+            });
+
+        match fetched {
+            Ok(row_offset) => errors.push((row_offset, error_get(row_errhp as *mut OCIError, "oci::stmt_execute_batch"))),
+            Err(err) => {
+                handle_free(row_errhp, OCI_HTYPE_ERROR);
+                return Err(err);
+            }
+        }
+    }
+
+    handle_free(row_errhp, OCI_HTYPE_ERROR);
+    Ok(errors)
+}
+
+/// affected rows of each row of binds after batch execution
+pub fn batch_row_counts(stmthp: *mut OCIStmt, errhp: *mut OCIError) -> OracleResult<Vec<u64>> {
+    let mut row_counts = ptr::null_mut() as *mut u64;
+    let count = attr_get(stmthp as *mut c_void, OCI_HTYPE_STMT, &mut row_counts as *mut *mut u64 as *mut c_void, OCI_ATTR_DML_ROW_COUNT_ARRAY, errhp)?;
+
+    if row_counts.is_null() {
+        return Ok(vec![]);
+    }
+    Ok( unsafe { std::slice::from_raw_parts(row_counts, count as usize) }.to_vec() )
+}
+
 /// defines an output buffer which will receive data retreived from Oracle
 #[inline]
 pub fn define_by_pos(stmthp: *mut OCIStmt,
@@ -353,6 +623,22 @@ pub fn bind_by_name(stmthp: *mut OCIStmt,
         }, Some(errhp), "oci::bind_by_name").map(|_| handle)
 }
 
+/// returns next implicit result (DBMS_SQL.RETURN_RESULT) of executed statement, it is owned by statement
+#[inline]
+pub fn stmt_get_next_result(stmthp: *mut OCIStmt, errhp: *mut OCIError) -> Result<Option<*mut OCIStmt>, OracleError> {
+    let mut result = ptr::null_mut() as *mut c_void;
+    let mut rtype: u32 = 0;
+
+    let error_code = unsafe {
+        OCIStmtGetNextResult(stmthp, errhp, &mut result, &mut rtype, OCI_DEFAULT)
+    };
+    if error_code == OCI_NO_DATA {
+        Ok(None)
+    } else {
+        check_error(error_code, Some(errhp), "oci::stmt_get_next_result").map(|_| Some(result as *mut OCIStmt))
+    }
+}
+
 /// fetches rows from a query
 #[inline]
 pub fn stmt_fetch(stmthp: *mut OCIStmt,
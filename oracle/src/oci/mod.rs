@@ -21,6 +21,7 @@ pub use bindings::{
     OCISPool,
     OCISvcCtx,
     OCIStmt,
+    OCILobLocator,
     c_void
 };
 
@@ -31,10 +32,40 @@ pub use constants::{
     OCI_HTYPE_SVCCTX,
     OCI_HTYPE_SESSION,
     OCI_HTYPE_STMT,
+    OCI_HTYPE_SPOOL,
+    OCI_DTYPE_INTERVAL_YM,
+    OCI_DTYPE_INTERVAL_DS,
+    OCI_DTYPE_TIMESTAMP,
+    OCI_DTYPE_TIMESTAMP_TZ,
+    OCI_DTYPE_TIMESTAMP_LTZ,
+    OCI_DTYPE_LOB,
+    OCI_DTYPE_FILE,
+    OCI_TEMP_BLOB,
+    OCI_TEMP_CLOB,
+    SQLCS_IMPLICIT,
+    SQLCS_NCHAR,
     OCI_ATTR_SERVER,
     OCI_ATTR_SESSION,
+    OCI_ATTR_CLIENT_IDENTIFIER,
+    OCI_ATTR_MODULE,
+    OCI_ATTR_ACTION,
+    OCI_ATTR_CLIENT_INFO,
+    OCI_ATTR_CALL_TIMEOUT,
     OCI_ATTR_ROWS_FETCHED,
-    OCI_FETCH_NEXT
+    OCI_FETCH_NEXT,
+    OCI_ATTR_SPOOL_STMTCACHESIZE,
+    OCI_ATTR_SPOOL_TIMEOUT,
+    OCI_ATTR_SPOOL_GETMODE,
+    OCI_ATTR_SPOOL_BUSY_COUNT,
+    OCI_ATTR_SPOOL_OPEN_COUNT,
+    OCI_ATTR_SPOOL_MIN,
+    OCI_ATTR_SPOOL_MAX,
+    OCI_ATTR_SPOOL_INCR,
+    OCI_ATTR_SPOOL_MAX_LIFETIME_SESSION,
+    OCI_ATTR_SPOOL_WAIT_TIMEOUT,
+    OCI_SPOOL_ATTRVAL_WAIT,
+    OCI_SPOOL_ATTRVAL_NOWAIT,
+    OCI_SPOOL_ATTRVAL_TIMEDWAIT
 };
 
 pub use error::{OracleError, OracleResult};
@@ -44,6 +75,25 @@ pub use functions::{
     terminate,
     handle_alloc,
     handle_free,
+    descriptor_alloc,
+    descriptor_free,
+    datetime_get,
+    datetime_get_offset,
+    datetime_construct,
+    interval_get_year_month,
+    interval_set_year_month,
+    interval_get_day_second,
+    interval_set_day_second,
+    lob_assign,
+    lob_charset_form,
+    lob_get_length,
+    lob_read,
+    lob_write,
+    lob_trim,
+    lob_create_temporary,
+    lob_free_temporary,
+    lob_file_open,
+    lob_file_close,
     server_attach,
     server_detach,
     attr_set,
@@ -55,12 +105,20 @@ pub use functions::{
     destroy_session_pool,
     session_get,
     session_release,
+    break_call,
+    reset,
     commit,
     rollback,
     stmt_prepare,
     stmt_release,
     stmt_execute,
+    stmt_execute_batch,
+    stmt_row_count,
+    stmt_is_query,
+    batch_errors,
+    batch_row_counts,
     stmt_fetch,
+    stmt_get_next_result,
     define_by_pos,
     bind_by_pos,
     bind_by_name,
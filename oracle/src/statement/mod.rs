@@ -19,6 +19,7 @@ pub use self::results::{
     SQLResults
 };
 pub use self::params::{
+    OutValues,
     ParamsProjection,
     ParamsProvider,
     ParamValue,
@@ -31,20 +32,45 @@ pub use self::query::{
 
 use crate::{OracleResult, OracleError};
 
+/// Result of batch execution: affected rows for each row of parameters and errors of failed rows
+#[derive(Debug)]
+pub struct BatchResult {
+    pub row_counts: Vec<u64>,
+    pub errors:     Vec<BatchError>
+}
+
+/// Error of one row in batch, row is index in parameters
+#[derive(Debug)]
+pub struct BatchError {
+    pub row:   usize,
+    pub error: OracleError
+}
+
 /// Generic prepared statement with parameters (bindings)
 /// Parameters may be () - Unit
 pub struct Statement<'conn, P> {
     conn:     &'conn Connection,
     stmthp:   *mut oci::OCIStmt,
     provider: Box<dyn ParamsProvider<P>>,
-    params:   ParamsProcessor
+    params:   ParamsProcessor,
+    // REF CURSOR or implicit result: executed by other statement and released with it
+    cursor:   bool
+}
+
+impl <'conn> Statement<'conn,()> {
+    /// Statement of executed cursor, it is fetched without execution
+    pub(crate) fn from_cursor(conn: &'conn Connection, stmthp: *mut oci::OCIStmt) -> OracleResult<Statement<'conn,()>> {
+        let provider: Box<dyn ParamsProvider<()>> = Box::new(());
+        let params = ParamsProcessor::new(conn, stmthp, provider.as_ref(), 1)?;
+        Ok( Statement { conn, stmthp, provider, params, cursor: true } )
+    }
 }
 
 impl <'conn,P> Statement<'conn,P> {
     pub(crate) fn new<'s>(conn: &'conn Connection, sql:  &'s str, provider: Box<dyn ParamsProvider<P>>) -> OracleResult<Statement<'conn,P>> {
         let stmthp = oci::stmt_prepare(conn.svchp, conn.errhp, sql)?;
-        let params = ParamsProcessor::new(conn, stmthp, provider.as_ref())?;
-        Ok( Statement { conn, stmthp, provider, params } )
+        let params = ParamsProcessor::new(conn, stmthp, provider.as_ref(), 1)?;
+        Ok( Statement { conn, stmthp, provider, params, cursor: false } )
     }
 
     /// Prepare oracle statement with prefetch rows == 10
@@ -70,17 +96,74 @@ impl <'conn,P> Statement<'conn,P> {
         Query::new(self, provider, prefetch_rows)
     }
 
-    /// Execute generic statement with params
-    pub fn execute(&self, params: P) -> OracleResult<()> {
+    /// Execute generic statement with params, returns count of rows affected by DML.
+    /// Non-query statements (DML, PL/SQL) must be executed with one iteration at least,
+    /// query is executed with zero iterations, its rows are not fetched
+    pub fn execute(&self, params: P) -> OracleResult<u64> {
         self.set_params(params)?;
-        oci::stmt_execute(self.conn.svchp, self.stmthp, self.conn.errhp, 0, 0).map(|_| ())
+        let iters = if oci::stmt_is_query(self.stmthp, self.conn.errhp)? { 0 } else { 1 };
+        oci::stmt_execute(self.conn.svchp, self.stmthp, self.conn.errhp, iters, 0)?;
+        oci::stmt_row_count(self.stmthp, self.conn.errhp)
+    }
+
+    /// Execute DML statement for all rows of params by one round trip (array DML).
+    /// Failed rows don't stop execution, their errors are returned with affected rows of each row
+    pub fn execute_batch(&self, params: &[P]) -> OracleResult<BatchResult> {
+        if params.is_empty() {
+            return Ok( BatchResult { row_counts: vec![], errors: vec![] } );
+        }
+
+        let batch = ParamsProcessor::new(self.conn, self.stmthp, self.provider.as_ref(), params.len())?;
+        {
+            let mut projection = batch.projection
+                .try_borrow_mut()
+                .map_err(|err|OracleError::new(format!("Can not borrow params-projection for batch: {}", err),"Statement::execute_batch"))?;
+            for (row, params) in params.iter().enumerate() {
+                batch.reset_outputs(&mut projection[row]);
+                self.provider.project_values(params, &mut projection[row]);
+            }
+        }
+
+        let executed = oci::stmt_execute_batch(self.conn.svchp, self.stmthp, self.conn.errhp, params.len() as u32);
+
+        // buffers of batch are released, statement is binded to own buffers again.
+        // error of execution has priority over error of rebinding
+        let rebound = self.params.bind(self.conn, self.stmthp);
+        executed?;
+        rebound?;
+
+        let errors = oci::batch_errors(self.conn.env.envhp, self.stmthp, self.conn.errhp)?
+            .into_iter()
+            .map(|(row, error)|BatchError { row: row as usize, error })
+            .collect();
+        let row_counts = oci::batch_row_counts(self.stmthp, self.conn.errhp)?;
+
+        Ok( BatchResult { row_counts, errors } )
+    }
+
+    /// Execute PL/SQL block with OUT and IN OUT parameters: `BEGIN pkg.proc(:a, :b); END;`,
+    /// or DML with RETURNING clause: `INSERT INTO t (name) VALUES (:1) RETURNING id INTO :2`.
+    /// Values of parameters are read back after execution
+    pub fn execute_out(&self, params: P) -> OracleResult<OutValues<'_>> {
+        let row_count = self.execute(params)?;
+        self.params.out_values(self.conn, row_count)
+    }
+
+    /// Next implicit result of executed PL/SQL block (`DBMS_SQL.RETURN_RESULT`), None after last one.
+    /// Result is fetched by query: `stmt.next_result()?.unwrap().query_many::<R>(100)?.fetch_list(())`
+    pub fn next_result(&self) -> OracleResult<Option<Statement<'_,()>>> {
+        match oci::stmt_get_next_result(self.stmthp, self.conn.errhp)? {
+            Some(stmthp) => Statement::from_cursor(self.conn, stmthp).map(Some),
+            None => Ok(None)
+        }
     }
 
     pub(crate) fn set_params(&self, params: P) -> OracleResult<()> {
         let mut projection = self.params.projection
             .try_borrow_mut()
             .map_err(|err|OracleError::new(format!("Can not borrow params-projection for set-params: {}", err),"Statement::set_params"))?;
-        self.provider.project_values(&params, projection.as_mut());
+        self.params.reset_outputs(&mut projection[0]);
+        self.provider.project_values(&params, &mut projection[0]);
         Ok(())
     }
 
@@ -88,6 +171,8 @@ impl <'conn,P> Statement<'conn,P> {
 
 impl <P> Drop for Statement<'_,P> {
     fn drop(&mut self) {
-        oci::stmt_release(self.stmthp, self.conn.errhp);
+        if !self.cursor {
+            oci::stmt_release(self.stmthp, self.conn.errhp);
+        }
     }
 }
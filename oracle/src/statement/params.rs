@@ -8,8 +8,12 @@ use crate::oci;
 
 use crate::connection::Connection;
 use crate::types::TypeDescriptor;
+use crate::{OracleResult, OracleError};
 use crate::statement::memory::align_size_to;
+use crate::statement::results::ResultValue;
+use crate::statement::Statement;
 use std::cell::RefCell;
+use std::ptr;
 
 pub struct ParamValue {
     valp: *mut u8,
@@ -20,8 +24,17 @@ pub struct ParamValue {
 
 pub struct Member {
     descriptor: TypeDescriptor,
-    identifier: Identifier
+    identifier: Identifier,
+    direction:  Direction
 }
+
+/// Direction of parameter, OUT and IN OUT parameters are used in PL/SQL blocks.
+/// Buffer of OUT parameter must have size for maximal returned value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    In, Out, InOut
+}
+
 pub enum Identifier {
     /// A named field like `self.x`.
     Named(&'static str),
@@ -49,7 +62,67 @@ pub trait ValueProjector<T> {
 
 impl Member {
     pub fn new(descriptor: TypeDescriptor,identifier: Identifier) -> Self {
-        Member { descriptor, identifier }
+        Member { descriptor, identifier, direction: Direction::In }
+    }
+
+    pub fn with_direction(descriptor: TypeDescriptor, identifier: Identifier, direction: Direction) -> Self {
+        Member { descriptor, identifier, direction }
+    }
+}
+
+/// Values of parameters after execution of statement, in order of members.
+/// Values point to buffers of statement, they are valid until next execution
+pub struct OutValues<'s> {
+    conn:      &'s Connection,
+    row_count: u64,
+    names:   Vec<Option<&'static str>>,
+    values:  Vec<ResultValue>,
+    cursors: Vec<*mut oci::OCIStmt>
+}
+
+impl <'s> OutValues<'s> {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// count of rows affected by DML, values of RETURNING clause are valid if it is 1
+    pub fn row_count(&self) -> u64 {
+        self.row_count
+    }
+
+    /// raw value of parameter by position (from 0)
+    pub fn value(&self, index: usize) -> ResultValue {
+        self.values[index]
+    }
+
+    /// typed value of parameter by position (from 0), panics if position is out of range
+    pub fn get<T: From<ResultValue>>(&self, index: usize) -> T {
+        self.values[index].into()
+    }
+
+    /// opened REF CURSOR by position (from 0), it is fetched by query:
+    /// `out.cursor(1)?.query_many::<R>(100)?.fetch_list(())`
+    pub fn cursor(&self, index: usize) -> OracleResult<Statement<'s, ()>> {
+        let stmthp = self.cursors[index];
+        if stmthp.is_null() {
+            return Err(OracleError::new(format!("Parameter {} is not a cursor", index + 1), "OutValues::cursor"));
+        }
+        if let ResultValue::Nil = self.values[index] {
+            return Err(OracleError::new(format!("Cursor {} is not opened", index + 1), "OutValues::cursor"));
+        }
+        Statement::from_cursor(self.conn, stmthp)
+    }
+
+    /// typed value of named parameter, name may be with or without colon
+    pub fn get_by_name<T: From<ResultValue>>(&self, name: &str) -> Option<T> {
+        let name = name.trim_start_matches(':');
+        self.names.iter()
+            .position(|n|n.is_some_and(|n|n.trim_start_matches(':').eq_ignore_ascii_case(name)))
+            .map(|idx|self.values[idx].into())
     }
 }
 
@@ -72,6 +145,26 @@ impl <'a> ParamValue {
         };
     }
 
+    /// Set NULL value, it is initial value of OUT parameter
+    #[inline]
+    pub fn project_null(&mut self) {
+        unsafe {
+            *self.indp = -1;
+        }
+    }
+
+    /// Value after execution: NULL by indicator, actual length is set by oracle for OUT parameters
+    #[inline]
+    pub fn value(&self) -> ResultValue {
+        unsafe {
+            if *self.indp == -1 {
+                ResultValue::Nil
+            } else {
+                ResultValue::Val { valp: self.valp as *const u8, len: *self.lenp as u16 }
+            }
+        }
+    }
+
     /// Convert non-optional type to row data
     #[inline]
     pub fn project<U, F>(&mut self, _param: &U, f: F)
@@ -89,25 +182,56 @@ impl <'a> ParamValue {
         }
     }
 
+    /// Copy bytes of string or binary value to row data, empty value is NULL.
+    /// value is truncated to size of buffer, it must be validated by caller before
+    #[inline]
+    pub fn project_bytes(&mut self, bytes: &[u8]) {
+        let size = self.size;
+        self.project(&bytes, |data, indp| {
+            let len = bytes.len().min(size);
+            unsafe {
+                if len == 0 {
+                    *indp = -1;
+                } else {
+                    ptr::copy(bytes.as_ptr(), data, len);
+                }
+            }
+            len
+        });
+    }
+
 }
 
 pub(crate) struct ParamsProcessor {
     allocated_p:   *mut u8,    // pointer to a main allocated block
     allocated_layout: Layout,  // layout of allocated block
 
-    // cache of allocated blocks to parameters
-    pub(crate) projection: RefCell<ParamsProjection>,
+    // cache of allocated blocks to parameters, one projection for each row of batch
+    pub(crate) projection: RefCell<Vec<ParamsProjection>>,
+
+    descriptors: Vec<TypeDescriptor>,
+    names:       Vec<Option<&'static str>>,
+    directions:  Vec<Direction>,
+    // statement handles of REF CURSOR parameters, null for other parameters
+    cursors:     Vec<*mut oci::OCIStmt>,
+    // datetime, interval and LOB descriptors of all rows with their types
+    oci_descriptors: Vec<(*mut oci::c_void, u32)>,
 }
 
 impl ParamsProcessor {
-    pub(crate) fn new<P>(conn: &Connection, stmthp: *mut oci::OCIStmt, provider: & dyn ParamsProvider<P>) -> Result<ParamsProcessor, oci::OracleError> {
+    /// allocate and bind buffers for `rows` rows of parameters, values of each parameter are stored as array
+    pub(crate) fn new<P>(conn: &Connection, stmthp: *mut oci::OCIStmt, provider: & dyn ParamsProvider<P>, rows: usize) -> Result<ParamsProcessor, oci::OracleError> {
         let members = provider.members();
         let columns_cnt = members.len();
 
+        if rows > 1 && members.iter().any(|m|m.descriptor.is_cursor()) {
+            return Err(OracleError::new("REF CURSOR parameters can not be executed in batch".to_string(), "ParamsProcessor::new"));
+        }
+
         let val_size = members.iter().map(|m| m.descriptor.size ).sum::<usize>();
-        let area_size = align_size_to(val_size, 128);
-        let inds_size = align_size_to(2 * columns_cnt, 64);
-        let lens_size = align_size_to(4 * columns_cnt, 64);
+        let area_size = align_size_to(val_size * rows, 128);
+        let inds_size = align_size_to(2 * columns_cnt * rows, 64);
+        let lens_size = align_size_to(4 * columns_cnt * rows, 64);
 
         let total_size = align_size_to(area_size + inds_size + lens_size, 256);
 
@@ -125,42 +249,114 @@ impl ParamsProcessor {
         let mut offset = 0;
         let mut offset_i = 0;
 
-        let mut sizes = Vec::with_capacity(columns_cnt);
-        let mut projection = Vec::with_capacity(columns_cnt);
+        let mut projection: Vec<ParamsProjection> = (0..rows).map(|_|Vec::with_capacity(columns_cnt)).collect();
+        let mut descriptors = Vec::with_capacity(columns_cnt);
+        let mut names = Vec::with_capacity(columns_cnt);
+        let mut directions = Vec::with_capacity(columns_cnt);
+        let mut cursors = Vec::with_capacity(columns_cnt);
+        let mut oci_descriptors = Vec::new();
 
         unsafe {
-            for (i,m) in members.iter().enumerate() {
+            for m in members.iter() {
                 let d = &m.descriptor;
-                let valp = values_p.offset(offset) as *mut libc::c_void;
-                let indp = indicators_p.offset(offset_i) as *mut libc::c_void;
-                let lenp = actual_lengths_p.offset(offset_i) as *mut u32;
-
-                offset += d.size as isize;
-                offset_i += 1 as isize;
-
-                match &m.identifier {
-                    Identifier::Named(name) => {
-                        oci::bind_by_name(stmthp, conn.errhp, name, valp, indp, d.size as i64, lenp, d.dtype)?;
-                    },
-                    Identifier::Unnamed => {
-                        oci::bind_by_pos(stmthp, conn.errhp, (i+1) as u32, valp, indp, d.size as i64, lenp, d.dtype)?;
+
+                for (row, row_projection) in projection.iter_mut().enumerate() {
+                    let valp = values_p.offset(offset + (d.size * row) as isize) as *mut u8;
+                    let indp = indicators_p.offset(offset_i + row as isize) as *mut i16;
+                    let lenp = actual_lengths_p.offset(offset_i + row as isize) as *mut u32;
+                    row_projection.push(ParamValue {valp, indp, lenp, size: d.size});
+                }
+
+                offset += (d.size * rows) as isize;
+                offset_i += rows as isize;
+
+                // cursor is binded by address of allocated statement handle
+                let cursor = if d.is_cursor() {
+                    let cursor = oci::handle_alloc(conn.env.envhp, oci::OCI_HTYPE_STMT)? as *mut oci::OCIStmt;
+                    *(projection[0].last().unwrap().valp as *mut *mut oci::OCIStmt) = cursor;
+                    cursor
+                } else {
+                    ptr::null_mut()
+                };
+                cursors.push(cursor);
+
+                // datetime, interval and LOB are binded by addresses of descriptors, one for each row
+                if let Some(dtype) = d.descriptor_type() {
+                    for row_projection in projection.iter() {
+                        let descriptor = oci::descriptor_alloc(conn.env.envhp, dtype)?;
+                        *(row_projection.last().unwrap().valp as *mut *mut oci::c_void) = descriptor;
+                        oci_descriptors.push((descriptor, dtype));
                     }
                 }
 
-                sizes.push(d.size as isize);
-                projection.push(ParamValue {valp: valp as *mut u8, indp: indp as *mut i16, lenp, size: d.size})
+                descriptors.push(*d);
+                names.push(match &m.identifier {
+                    Identifier::Named(name) => Some(*name),
+                    Identifier::Unnamed => None
+                });
+                directions.push(m.direction);
             }
         }
 
         let projection = RefCell::new(projection);
 
-        Ok(ParamsProcessor {allocated_p, allocated_layout, projection})
+        let processor = ParamsProcessor {allocated_p, allocated_layout, projection, descriptors, names, directions, cursors, oci_descriptors};
+        processor.bind(conn, stmthp)?;
+        Ok(processor)
+    }
+
+    /// bind buffers to placeholders of statement, binding replaces buffers of previous binding
+    pub(crate) fn bind(&self, conn: &Connection, stmthp: *mut oci::OCIStmt) -> OracleResult<()> {
+        let projection = self.projection
+            .try_borrow()
+            .map_err(|err|OracleError::new(format!("Can not borrow params-projection for bind: {}", err),"ParamsProcessor::bind"))?;
+
+        if let Some(first_row) = projection.first() {
+            for (i, p) in first_row.iter().enumerate() {
+                let d = &self.descriptors[i];
+                let valp = p.valp as *mut libc::c_void;
+                let indp = p.indp as *mut libc::c_void;
+                // cursor is binded with zero size
+                let bind_size = if d.is_cursor() { 0 } else { d.size as i64 };
+
+                match self.names[i] {
+                    Some(name) => oci::bind_by_name(stmthp, conn.errhp, name, valp, indp, bind_size, p.lenp, d.dtype)?,
+                    None => oci::bind_by_pos(stmthp, conn.errhp, (i+1) as u32, valp, indp, bind_size, p.lenp, d.dtype)?
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    /// OUT parameters are NULL before execution, provider may skip them
+    pub(crate) fn reset_outputs(&self, projection: &mut ParamsProjection) {
+        for (p, direction) in projection.iter_mut().zip(self.directions.iter()) {
+            if *direction == Direction::Out {
+                p.project_null();
+            }
+        }
+    }
+
+    /// read back values of all parameters after execution
+    pub(crate) fn out_values<'s>(&'s self, conn: &'s Connection, row_count: u64) -> OracleResult<OutValues<'s>> {
+        let projection = self.projection
+            .try_borrow()
+            .map_err(|err|OracleError::new(format!("Can not borrow params-projection for out-values: {}", err),"ParamsProcessor::out_values"))?;
+        let values = projection[0].iter().map(|p|p.value()).collect();
+        Ok( OutValues { conn, row_count, names: self.names.clone(), values, cursors: self.cursors.clone() } )
     }
 
 }
 
 impl Drop for ParamsProcessor {
     fn drop(&mut self) {
+        for cursor in self.cursors.iter().filter(|c|!c.is_null()) {
+            oci::handle_free(*cursor as *mut oci::c_void, oci::OCI_HTYPE_STMT);
+        }
+        for (descriptor, dtype) in self.oci_descriptors.iter() {
+            oci::descriptor_free(*descriptor, *dtype);
+        }
         unsafe { dealloc(self.allocated_p, self.allocated_layout); };
     }
 }
@@ -29,7 +29,7 @@ pub struct QueryIterator<'iter, 'conn: 'iter, P, R: 'conn> {
 
 impl <'conn,P,R: 'conn> Query<'conn,P,R> {
     pub(crate) fn new(stmt: Statement<'conn,P>, provider: Box<dyn ResultsProvider<R>>, prefetch_rows: usize) -> OracleResult<Query<'conn,P,R>> {
-        let results = Box::new( ResultProcessor::new(stmt.conn, stmt.stmthp, provider.as_ref(), prefetch_rows)? );
+        let results = Box::new( ResultProcessor::new(stmt.conn, stmt.stmthp, provider.as_ref(), prefetch_rows, stmt.cursor)? );
         Ok( Query { stmt, prefetch_rows, provider, results })
     }
 
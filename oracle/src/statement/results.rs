@@ -65,6 +65,7 @@ pub(crate) struct ResultProcessor<'conn> {
 
     prefetch_rows: usize,
     sizes:         Vec<isize>,
+    executed:      bool,       // cursor is fetched without execution
 
     allocated_p:   *mut u8,    // pointer to a main allocated block
     allocated_layout: Layout,  // layout of allocated block
@@ -72,6 +73,9 @@ pub(crate) struct ResultProcessor<'conn> {
     values_p:      *const u8,  // pointer to values area
     indicators_p:  *const i16, // pointer to indicators area
     ret_lengths_p: *const u16, // pointer to return length area,
+
+    // datetime, interval and LOB descriptors of all prefetched rows with their types
+    oci_descriptors: Vec<(*mut oci::c_void, u32)>,
 }
 
 pub struct ResultIterator<'iter, 'conn: 'iter> {
@@ -84,7 +88,7 @@ pub struct ResultIterator<'iter, 'conn: 'iter> {
 
 impl <'conn> ResultProcessor<'conn> {
 
-    pub(crate) fn new<'p, R>(conn: &'conn Connection, stmthp: *mut oci::OCIStmt, provider: &'p dyn ResultsProvider<R>, prefetch_rows: usize)
+    pub(crate) fn new<'p, R>(conn: &'conn Connection, stmthp: *mut oci::OCIStmt, provider: &'p dyn ResultsProvider<R>, prefetch_rows: usize, executed: bool)
                       -> Result<ResultProcessor<'conn>, oci::OracleError> {
         let descriptors = provider.sql_descriptors();
         let columns_cnt = descriptors.len();
@@ -111,6 +115,7 @@ impl <'conn> ResultProcessor<'conn> {
 
         let mut offset = 0;
         let mut offset_i = 0;
+        let mut oci_descriptors = Vec::new();
 
         unsafe {
             for (i,d) in descriptors.iter().enumerate() {
@@ -118,6 +123,15 @@ impl <'conn> ResultProcessor<'conn> {
                 let ind_p = indicators_p.offset(offset_i) as *mut libc::c_void;
                 let rlen_p = ret_lengths_p.offset(offset_i) as *mut u16;
 
+                // datetime, interval and LOB are defined as array of descriptors
+                if let Some(dtype) = d.descriptor_type() {
+                    for row in 0..prefetch_rows {
+                        let descriptor = oci::descriptor_alloc(conn.env.envhp, dtype)?;
+                        *(value_p as *mut *mut oci::c_void).add(row) = descriptor;
+                        oci_descriptors.push((descriptor, dtype));
+                    }
+                }
+
                 offset += (d.size * prefetch_rows) as isize;
                 offset_i += prefetch_rows as isize;
 
@@ -129,7 +143,7 @@ impl <'conn> ResultProcessor<'conn> {
 
         oci::set_prefetch_size(stmthp, conn.errhp, prefetch_rows as u32)?;
 
-        Ok( ResultProcessor {conn, stmthp, prefetch_rows, sizes, allocated_p, allocated_layout, values_p, indicators_p, ret_lengths_p} )
+        Ok( ResultProcessor {conn, stmthp, prefetch_rows, sizes, executed, allocated_p, allocated_layout, values_p, indicators_p, ret_lengths_p, oci_descriptors} )
     }
 
     fn get_last_fetched_rows(&self) -> OracleResult<u32> {
@@ -141,6 +155,12 @@ impl <'conn> ResultProcessor<'conn> {
     }
 
     pub (crate) fn fetch_iter<'iter> (&'conn self) -> OracleResult<ResultIterator<'iter, 'conn>> {
+        if self.executed {
+            // first rows of cursor are fetched, not prefetched by execution
+            let (rows_fetched, _) = self.fetch_next()?;
+            return Ok( ResultIterator::new(&self, rows_fetched) );
+        }
+
         let iters = self.prefetch_rows as u32;
         let success = oci::stmt_execute(self.conn.svchp, self.stmthp, self.conn.errhp, iters, 0)?;
 
@@ -210,6 +230,9 @@ impl <'conn> ResultProcessor<'conn> {
 
 impl Drop for ResultProcessor<'_> {
     fn drop(&mut self) {
+        for (descriptor, dtype) in self.oci_descriptors.iter() {
+            oci::descriptor_free(*descriptor, *dtype);
+        }
         unsafe { dealloc(self.allocated_p, self.allocated_layout); };
     }
 }
@@ -0,0 +1,74 @@
+use std::ops::Deref;
+
+use crate::connection::Connection;
+use crate::{OracleResult, OracleError};
+
+/// Transaction of connection, it is rolled back on drop unless committed.
+/// Statements are prepared and executed through transaction (it derefs to connection)
+pub struct Transaction<'conn> {
+    conn: &'conn Connection,
+    done: bool
+}
+
+impl <'conn> Transaction<'conn> {
+    pub(crate) fn new(conn: &'conn Connection) -> Transaction<'conn> {
+        Transaction { conn, done: false }
+    }
+
+    /// commit transaction, it is rolled back on drop if commit fails
+    pub fn commit(mut self) -> OracleResult<()> {
+        self.conn.commit()?;
+        self.done = true;
+        Ok(())
+    }
+
+    /// rollback whole transaction
+    pub fn rollback(mut self) -> OracleResult<()> {
+        self.done = true;
+        self.conn.rollback()
+    }
+
+    /// set savepoint, savepoint with the same name is moved
+    pub fn savepoint(&self, name: &str) -> OracleResult<()> {
+        check_savepoint_name(name)?;
+        self.conn.execute(&format!("SAVEPOINT {}", name)).map(|_| ())
+    }
+
+    /// rollback changes after savepoint, savepoint remains and can be used again
+    pub fn rollback_to(&self, name: &str) -> OracleResult<()> {
+        check_savepoint_name(name)?;
+        self.conn.execute(&format!("ROLLBACK TO SAVEPOINT {}", name)).map(|_| ())
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            // session must be returned to pool without uncommitted changes
+            if let Err(err) = self.conn.rollback() {
+                eprintln!("WARNING: Can not rollback transaction: {}", err);
+            }
+        }
+    }
+}
+
+/// savepoint name is inserted in sql, it must be simple identifier
+fn check_savepoint_name(name: &str) -> OracleResult<()> {
+    let valid = name.chars().next().is_some_and(|c|c.is_ascii_alphabetic())
+        && name.len() <= 128
+        && name.chars().all(|c|c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '#');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(OracleError::new(format!("Invalid savepoint name '{}'", name), "Transaction::savepoint"))
+    }
+}
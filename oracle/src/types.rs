@@ -1,5 +1,7 @@
 use std::mem::size_of;
 
+use crate::oci;
+
 // Oracle Types, must be u16
 #[allow(dead_code)]
 #[allow(non_snake_case)]
@@ -60,7 +62,9 @@ mod constants {
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SqlType {
-    Int16, Int32, Int64, Float64, Varchar, DateTime, Blob, Clob, Long, Unsupported
+    Int16, Int32, Int64, Float64, Decimal, Varchar, DateTime,
+    Timestamp, TimestampTz, TimestampLtz, IntervalYM, IntervalDS,
+    Raw, LongRaw, Blob, Clob, NClob, BFile, Long, Unsupported
 }
 
 /// incapsulate Oracle SQL Types
@@ -78,6 +82,37 @@ impl TypeDescriptor {
     pub const fn new(dtype: u16, size: usize) -> TypeDescriptor {
         TypeDescriptor { dtype, size }
     }
+
+    /// maximal length in bytes of string or binary value, buffer of string has 2 bytes more
+    pub fn capacity(&self) -> usize {
+        match self.dtype {
+            constants::SQLT_CHR => self.size - 2,
+            _ => self.size
+        }
+    }
+
+    /// REF CURSOR (SYS_REFCURSOR) parameter, buffer contains statement handle
+    pub const fn cursor() -> TypeDescriptor {
+        CURSOR_SQLTYPE
+    }
+
+    pub(crate) fn is_cursor(&self) -> bool {
+        self.dtype == constants::SQLT_RSET
+    }
+
+    /// type of OCI descriptor for datetime, interval and LOB types, buffer contains pointer to descriptor
+    pub(crate) fn descriptor_type(&self) -> Option<u32> {
+        match self.dtype {
+            constants::SQLT_CLOB | constants::SQLT_BLOB => Some(oci::OCI_DTYPE_LOB),
+            constants::SQLT_BFILEE => Some(oci::OCI_DTYPE_FILE),
+            constants::SQLT_TIMESTAMP => Some(oci::OCI_DTYPE_TIMESTAMP),
+            constants::SQLT_TIMESTAMP_TZ => Some(oci::OCI_DTYPE_TIMESTAMP_TZ),
+            constants::SQLT_TIMESTAMP_LTZ => Some(oci::OCI_DTYPE_TIMESTAMP_LTZ),
+            constants::SQLT_INTERVAL_YM => Some(oci::OCI_DTYPE_INTERVAL_YM),
+            constants::SQLT_INTERVAL_DS => Some(oci::OCI_DTYPE_INTERVAL_DS),
+            _ => None
+        }
+    }
 }
 
 impl From<(SqlType, usize)> for TypeDescriptor {
@@ -87,9 +122,20 @@ impl From<(SqlType, usize)> for TypeDescriptor {
             SqlType::Int32 => I32_SQLTYPE,
             SqlType::Int64 => I64_SQLTYPE,
             SqlType::Float64 => F64_SQLTYPE,
+            SqlType::Decimal => DECIMAL_SQLTYPE,
             SqlType::DateTime => DATETIME_SQLTYPE,
+            SqlType::Timestamp => TIMESTAMP_SQLTYPE,
+            SqlType::TimestampTz => TIMESTAMP_TZ_SQLTYPE,
+            SqlType::TimestampLtz => TIMESTAMP_LTZ_SQLTYPE,
+            SqlType::IntervalYM => INTERVAL_YM_SQLTYPE,
+            SqlType::IntervalDS => INTERVAL_DS_SQLTYPE,
+            SqlType::Blob => BLOB_SQLTYPE,
+            SqlType::Clob | SqlType::NClob => CLOB_SQLTYPE,
+            SqlType::BFile => BFILE_SQLTYPE,
             SqlType::Long => string_sqltype(4000),
             SqlType::Varchar => string_sqltype(value.1),
+            SqlType::LongRaw => LONG_RAW_SQLTYPE,
+            SqlType::Raw => binary_sqltype(value.1),
             _ => panic!("Unsupported SQL type!")
         }
     }
@@ -102,9 +148,20 @@ impl From<SqlType> for TypeDescriptor {
             SqlType::Int32 => I32_SQLTYPE,
             SqlType::Int64 => I64_SQLTYPE,
             SqlType::Float64 => F64_SQLTYPE,
+            SqlType::Decimal => DECIMAL_SQLTYPE,
             SqlType::DateTime => DATETIME_SQLTYPE,
+            SqlType::Timestamp => TIMESTAMP_SQLTYPE,
+            SqlType::TimestampTz => TIMESTAMP_TZ_SQLTYPE,
+            SqlType::TimestampLtz => TIMESTAMP_LTZ_SQLTYPE,
+            SqlType::IntervalYM => INTERVAL_YM_SQLTYPE,
+            SqlType::IntervalDS => INTERVAL_DS_SQLTYPE,
+            SqlType::Blob => BLOB_SQLTYPE,
+            SqlType::Clob | SqlType::NClob => CLOB_SQLTYPE,
+            SqlType::BFile => BFILE_SQLTYPE,
             SqlType::Long => string_sqltype(4000),
             SqlType::Varchar => string_sqltype(128),
+            SqlType::LongRaw => LONG_RAW_SQLTYPE,
+            SqlType::Raw => binary_sqltype(MAX_RAW_SIZE),
             _ => panic!("Unsupported SQL type!")
         }
     }
@@ -128,13 +185,29 @@ type_desc!(u64, U64_SQLTYPE, SQLT_INT);
 // Float type
 type_desc!(f64, F64_SQLTYPE, SQLT_FLT);
 
+// Decimal type, Oracle NUMBER in internal format has at most 22 bytes
+pub const DECIMAL_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_NUM, 22 );
+
 // Boolean type
 type_desc!(bool, BOOL_SQLTYPE, SQLT_INT);
 
 // Date and Datetime type
 pub const DATE_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_DAT, 7 );
 pub const DATETIME_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_DAT, 7 );
-// pub const TIMESTAMP_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_TIMESTAMP, 11 );
+// Cursor type
+pub const CURSOR_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_RSET, size_of::<*mut u8>() );
+
+// Timestamp and interval types, buffer contains pointer to descriptor
+pub const TIMESTAMP_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_TIMESTAMP, size_of::<*mut u8>() );
+pub const TIMESTAMP_TZ_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_TIMESTAMP_TZ, size_of::<*mut u8>() );
+pub const TIMESTAMP_LTZ_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_TIMESTAMP_LTZ, size_of::<*mut u8>() );
+pub const INTERVAL_YM_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_INTERVAL_YM, size_of::<*mut u8>() );
+pub const INTERVAL_DS_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_INTERVAL_DS, size_of::<*mut u8>() );
+
+// LOB types, buffer contains pointer to locator. NCLOB is defined as CLOB, locator has its character set form
+pub const CLOB_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_CLOB, size_of::<*mut u8>() );
+pub const BLOB_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_BLOB, size_of::<*mut u8>() );
+pub const BFILE_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_BFILEE, size_of::<*mut u8>() );
 
 pub trait TypeDescriptorProducer<T> {
     fn produce() -> TypeDescriptor {
@@ -167,6 +240,11 @@ impl_descriptors_producer!(u64, U64_SQLTYPE);
 // Float type
 impl_descriptors_producer!(f64, F64_SQLTYPE);
 
+// Decimal type, exact value of NUMBER with 28 significant digits
+pub type SqlDecimal = rust_decimal::Decimal;
+
+impl_descriptors_producer!(SqlDecimal, DECIMAL_SQLTYPE);
+
 // Boolean type
 impl_descriptors_producer!(bool, BOOL_SQLTYPE);
 
@@ -195,14 +273,42 @@ impl TypeDescriptorProducer<&str> for &str {
     }
 }
 
+// all about binary type, RAW and LONG RAW. in Oracle NULL RAW is empty RAW
+
+/// maximal size of RAW in bytes, it is default capacity of binary parameters
+const MAX_RAW_SIZE: usize = 2000;
+
+// LONG RAW is fetched as LONG, up to 4000 bytes
+pub const LONG_RAW_SQLTYPE: TypeDescriptor = TypeDescriptor::new(constants::SQLT_LBI, 4000 );
+
+pub fn binary_sqltype(capacity: usize) -> TypeDescriptor {
+    TypeDescriptor::new(constants::SQLT_BIN, capacity)
+}
+
+impl TypeDescriptorProducer<Vec<u8>> for Vec<u8> {
+    fn produce() -> TypeDescriptor {
+        Self::produce_sized(MAX_RAW_SIZE)
+    }
+    fn produce_sized(capacity: usize) -> TypeDescriptor {
+        binary_sqltype(capacity)
+    }
+}
+
+impl TypeDescriptorProducer<&[u8]> for &[u8] {
+    fn produce() -> TypeDescriptor {
+        Self::produce_sized(MAX_RAW_SIZE)
+    }
+    fn produce_sized(capacity: usize) -> TypeDescriptor {
+        binary_sqltype(capacity)
+    }
+}
+
 // all about dates
 use chrono::prelude::*;
+use chrono::Duration;
 use std::convert::TryFrom;
 
-// Date and Datetime
-
-// TODO: Oracle Datetime have 7 bytes
-// TODO: Oracle Timestamp have 11 bytes
+// Date and Datetime, Oracle DATE has 7 bytes without fractional seconds
 
 // converts to Oracle Datetime (4 bytes)
 pub type SqlDate = Date<Local>;
@@ -210,11 +316,27 @@ pub type SqlDate = Date<Local>;
 // converts to Oracle Datetime (7 bytes)
 pub type SqlDateTime = DateTime<Local>;
 
-// converts to Oracle Timestamp (11 bytes)
-// pub type SqlTimestamp = DateTime<Local>;
+// Timestamps and intervals are converted through OCI descriptors, with fractional seconds in nanoseconds
 
-// TODO: inconsistency between timestamp and datetime
+// converts to Oracle TIMESTAMP
+pub type SqlTimestamp = NaiveDateTime;
+
+// converts to Oracle TIMESTAMP WITH TIME ZONE, TIMESTAMP WITH LOCAL TIME ZONE is in time zone of session
+pub type SqlTimestampTz = DateTime<FixedOffset>;
+
+// converts to Oracle INTERVAL DAY TO SECOND
+pub type SqlIntervalDS = Duration;
+
+/// Oracle INTERVAL YEAR TO MONTH, months have the same sign as years
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SqlIntervalYM {
+    pub years:  i32,
+    pub months: i32,
+}
 
 impl_descriptors_producer!(SqlDate, DATE_SQLTYPE);
 impl_descriptors_producer!(SqlDateTime, DATE_SQLTYPE);
-// impl_descriptors_producer!(SqlTimestamp, TIMESTAMP_SQLTYPE);
+impl_descriptors_producer!(SqlTimestamp, TIMESTAMP_SQLTYPE);
+impl_descriptors_producer!(SqlTimestampTz, TIMESTAMP_TZ_SQLTYPE);
+impl_descriptors_producer!(SqlIntervalYM, INTERVAL_YM_SQLTYPE);
+impl_descriptors_producer!(SqlIntervalDS, INTERVAL_DS_SQLTYPE);
@@ -3,7 +3,6 @@ use std::ptr;
 
 use crate::statement::{ParamValue, ResultValue};
 use crate::ValueProjector;
-use crate::SqlType;
 
 // integer types, must be used only for primitive types
 // TODO: optional types (ValueProjector)
@@ -67,33 +66,39 @@ impl From<ResultValue> for String {
 
 impl ValueProjector<String> for String {
     fn project_value(&self, projection: &mut ParamValue) {
-        projection.project(self, |data, indp| {
-            let str_len = self.len();
-            unsafe {
-                if str_len == 0 {
-                    *indp = -1;
-                } else {
-                    ptr::copy(self.as_ptr(), data, str_len);
-                }
-                str_len
-            }
-        });
+        projection.project_bytes(self.as_bytes());
     }
 }
 
 impl ValueProjector<&str> for &str {
     fn project_value(&self, projection: &mut ParamValue) {
-        projection.project(self, | data, indp| {
-            let str_len = self.len();
-            unsafe {
-                if str_len == 0 {
-                    *indp = -1;
-                } else {
-                    ptr::copy(self.as_ptr(), data, str_len);
-                }
-                str_len
-            }
-        });
+        projection.project_bytes(self.as_bytes());
+    }
+}
+
+// binary type (RAW), in Oracle NULL RAW is empty RAW
+
+impl From<ResultValue> for Vec<u8> {
+    fn from(v: ResultValue) -> Vec<u8> {
+        v.map_or(Vec::new(), |valp, len| unsafe { std::slice::from_raw_parts(valp, len as usize) }.to_vec())
+    }
+}
+
+impl From<ResultValue> for Option<Vec<u8>> {
+    fn from(v: ResultValue) -> Option<Vec<u8>> {
+        v.map(|valp, len| unsafe { std::slice::from_raw_parts(valp, len as usize) }.to_vec())
+    }
+}
+
+impl ValueProjector<Vec<u8>> for Vec<u8> {
+    fn project_value(&self, projection: &mut ParamValue) {
+        self.as_slice().project_value(projection);
+    }
+}
+
+impl ValueProjector<&[u8]> for &[u8] {
+    fn project_value(&self, projection: &mut ParamValue) {
+        projection.project_bytes(self);
     }
 }
 
@@ -223,86 +228,336 @@ fn datetime_to_row(source: &DateTime<Local>, data: *mut u8) -> usize {
     }
 }
 
-use std::convert::TryFrom;
+// Timestamps and intervals, buffer contains pointer to OCI descriptor.
+// descriptors are allocated with buffers, so errors of conversion are not expected
+use chrono::Duration;
+use crate::oci;
+use crate::environment::Environment;
+use crate::types::{SqlTimestamp, SqlTimestampTz, SqlIntervalYM, SqlIntervalDS};
 
-impl ResultValue {
-    fn null_value_repr() -> String {
-        "null".to_string()
+impl From<ResultValue> for SqlTimestamp {
+    fn from(v: ResultValue) -> SqlTimestamp {
+        v.map_or(Local::now().naive_local(), timestamp_from_row)
     }
+}
 
-    pub fn try_to_string(self, tp: &SqlType, nullable: bool) -> Result<String, &'static str> {
-        let result = match tp {
-            SqlType::Varchar => {
-                let v: String = self.into();
-                format!("\"{}\"",v)
-            },
-            SqlType::Int16 => {
-                if nullable {
-                    let v: Option<i16> = self.into();
-                    match v {
-                        None => ResultValue::null_value_repr(),
-                        Some (v) => v.to_string()
-                    }
-                } else {
-                    let v: i16 = self.into();
-                    v.to_string()
-                }
-            },
-            SqlType::Int32 => {
-                if nullable {
-                    let v: Option<i32> = self.into();
-                    match v {
-                        None => ResultValue::null_value_repr(),
-                        Some (v) => v.to_string()
-                    }
-                } else {
-                    let v: i32 = self.into();
-                    v.to_string()
-                }
-            },
-            SqlType::Int64 => {
-                if nullable {
-                    let v: Option<i64> = self.into();
-                    match v {
-                        None => ResultValue::null_value_repr(),
-                        Some (v) => v.to_string()
-                    }
-                } else {
-                    let v: i64 = self.into();
-                    v.to_string()
-                }
-            },
-            SqlType::Float64 => {
-                if nullable {
-                    let v: Option<f64> = self.into();
-                    match v {
-                        None => ResultValue::null_value_repr(),
-                        Some (v) => v.to_string()
-                    }
-                } else {
-                    let v: f64 = self.into();
-                    v.to_string()
-                }
-            },
-            SqlType::DateTime => {
-                if nullable {
-                    let v: Option<SqlDateTime> = self.into();
-                    match v {
-                        None => ResultValue::null_value_repr(),
-                        Some (v) => {
-                            let v: SqlDateTime = self.into();
-                            format!("\"{}\"", v.to_rfc3339())
-                        }
-                    }
-                } else {
-                    let v: SqlDateTime = self.into();
-                    format!("\"{}\"", v.to_rfc3339())
-                }
-            }
-            _ => return Err("\"not-implemented\"")
-        };
-        Ok(result)
+impl From<ResultValue> for Option<SqlTimestamp> {
+    fn from(v: ResultValue) -> Option<SqlTimestamp> {
+        v.map(timestamp_from_row)
+    }
+}
+
+impl From<ResultValue> for SqlTimestampTz {
+    fn from(v: ResultValue) -> SqlTimestampTz {
+        v.map_or(Local::now().into(), timestamp_tz_from_row)
     }
 }
 
+impl From<ResultValue> for Option<SqlTimestampTz> {
+    fn from(v: ResultValue) -> Option<SqlTimestampTz> {
+        v.map(timestamp_tz_from_row)
+    }
+}
+
+impl From<ResultValue> for SqlIntervalYM {
+    fn from(v: ResultValue) -> SqlIntervalYM {
+        v.map_or(Default::default(), interval_ym_from_row)
+    }
+}
+
+impl From<ResultValue> for Option<SqlIntervalYM> {
+    fn from(v: ResultValue) -> Option<SqlIntervalYM> {
+        v.map(interval_ym_from_row)
+    }
+}
+
+impl From<ResultValue> for SqlIntervalDS {
+    fn from(v: ResultValue) -> SqlIntervalDS {
+        v.map_or(Duration::zero(), interval_ds_from_row)
+    }
+}
+
+impl From<ResultValue> for Option<SqlIntervalDS> {
+    fn from(v: ResultValue) -> Option<SqlIntervalDS> {
+        v.map(interval_ds_from_row)
+    }
+}
+
+impl ValueProjector<SqlTimestamp> for SqlTimestamp {
+    fn project_value(&self, projection: &mut ParamValue) {
+        projection.project(self, |data, _| timestamp_to_row(self, None, data));
+    }
+}
+
+impl ValueProjector<SqlTimestampTz> for SqlTimestampTz {
+    fn project_value(&self, projection: &mut ParamValue) {
+        let timezone = self.offset().to_string();
+        projection.project(self, |data, _| timestamp_to_row(&self.naive_local(), Some(&timezone), data));
+    }
+}
+
+impl ValueProjector<SqlIntervalYM> for SqlIntervalYM {
+    fn project_value(&self, projection: &mut ParamValue) {
+        projection.project(self, |data, _| interval_ym_to_row(self, data));
+    }
+}
+
+impl ValueProjector<SqlIntervalDS> for SqlIntervalDS {
+    fn project_value(&self, projection: &mut ParamValue) {
+        projection.project(self, |data, _| interval_ds_to_row(self, data));
+    }
+}
+
+#[inline]
+fn descriptor_of<T>(valp: *const u8) -> *mut T {
+    unsafe { *(valp as *const *mut T) }
+}
+
+fn timestamp_from_row(valp: *const u8, _len: u16) -> NaiveDateTime {
+    let env = Environment::get().unwrap();
+    let (y, m, d, hh, mm, ss, ns) = oci::datetime_get(env.envhp, env.errhp, descriptor_of(valp))
+        .expect("Can not get value of Oracle Timestamp");
+
+    NaiveDate::from_ymd(y as i32, m as u32, d as u32).and_hms_nano(hh as u32, mm as u32, ss as u32, ns)
+}
+
+/// date and time of timestamp with time zone are in its time zone
+fn timestamp_tz_from_row(valp: *const u8, len: u16) -> DateTime<FixedOffset> {
+    let env = Environment::get().unwrap();
+    let (oh, om) = oci::datetime_get_offset(env.envhp, env.errhp, descriptor_of(valp))
+        .expect("Can not get time zone of Oracle Timestamp");
+
+    let offset = FixedOffset::east(oh as i32 * 3600 + om as i32 * 60);
+    offset.from_local_datetime(&timestamp_from_row(valp, len)).unwrap()
+}
 
+fn timestamp_to_row(source: &NaiveDateTime, timezone: Option<&str>, data: *mut u8) -> usize {
+    let env = Environment::get().unwrap();
+    let value = (source.year() as i16, source.month() as u8, source.day() as u8,
+                 source.hour() as u8, source.minute() as u8, source.second() as u8, source.nanosecond());
+    oci::datetime_construct(env.envhp, env.errhp, descriptor_of(data), value, timezone)
+        .expect("Can not set value of Oracle Timestamp");
+    0
+}
+
+fn interval_ym_from_row(valp: *const u8, _len: u16) -> SqlIntervalYM {
+    let env = Environment::get().unwrap();
+    let (years, months) = oci::interval_get_year_month(env.envhp, env.errhp, descriptor_of(valp))
+        .expect("Can not get value of Oracle Interval");
+    SqlIntervalYM { years, months }
+}
+
+fn interval_ym_to_row(source: &SqlIntervalYM, data: *mut u8) -> usize {
+    let env = Environment::get().unwrap();
+    oci::interval_set_year_month(env.envhp, env.errhp, descriptor_of(data), source.years, source.months)
+        .expect("Can not set value of Oracle Interval");
+    0
+}
+
+fn interval_ds_from_row(valp: *const u8, _len: u16) -> Duration {
+    let env = Environment::get().unwrap();
+    let (d, hh, mm, ss, ns) = oci::interval_get_day_second(env.envhp, env.errhp, descriptor_of(valp))
+        .expect("Can not get value of Oracle Interval");
+
+    Duration::days(d as i64) + Duration::hours(hh as i64) + Duration::minutes(mm as i64)
+        + Duration::seconds(ss as i64) + Duration::nanoseconds(ns as i64)
+}
+
+/// all parts of interval have the same sign, seconds are truncated toward zero
+fn interval_ds_to_row(source: &Duration, data: *mut u8) -> usize {
+    let env = Environment::get().unwrap();
+    let secs = source.num_seconds();
+    let nanos = (*source - Duration::seconds(secs)).num_nanoseconds().unwrap_or(0);
+    let value = ((secs / 86400) as i32, (secs % 86400 / 3600) as i32, (secs % 3600 / 60) as i32, (secs % 60) as i32, nanos as i32);
+
+    oci::interval_set_day_second(env.envhp, env.errhp, descriptor_of(data), value)
+        .expect("Can not set value of Oracle Interval");
+    0
+}
+
+// Decimal, Oracle NUMBER in internal format: exponent byte and up to 20 base-100 digits.
+// positive: exponent is 193 + e, digits are d + 1. negative: exponent is 62 - e, digits are 101 - d and 102 terminates them.
+// value is d0 * 100^e + d1 * 100^(e-1) + ..., digits beyond precision of Decimal are rounded.
+// number out of range of Decimal is converted to its exact text, it is never clamped
+use crate::types::SqlDecimal;
+
+/// maximal mantissa of Decimal, 96 bits
+const DECIMAL_MAX_MANTISSA: i128 = (1 << 96) - 1;
+const DECIMAL_MAX_SCALE: i32 = 28;
+
+/// Decimal or exact text of number which is out of range of Decimal, NULL is zero.
+/// it is the only conversion of NUMBER to Decimal, any NUMBER may be out of its range
+impl From<ResultValue> for Result<SqlDecimal, String> {
+    fn from(v: ResultValue) -> Result<SqlDecimal, String> {
+        v.map_or(Ok(Default::default()), decimal_from_row)
+    }
+}
+
+/// Decimal or exact text of number which is out of range of Decimal, None is NULL
+impl From<ResultValue> for Option<Result<SqlDecimal, String>> {
+    fn from(v: ResultValue) -> Option<Result<SqlDecimal, String>> {
+        v.map(decimal_from_row)
+    }
+}
+
+impl ValueProjector<SqlDecimal> for SqlDecimal {
+    fn project_value(&self, projection: &mut ParamValue) {
+        projection.project(self, |data, _| decimal_to_row(self, data));
+    }
+}
+
+/// Decimal value, Err is exact text of number which is out of range of Decimal
+fn decimal_from_row(valp: *const u8, len: u16) -> Result<SqlDecimal, String> {
+    let bytes = unsafe { std::slice::from_raw_parts(valp, len as usize) };
+    if bytes.is_empty() || bytes == [0x80] {
+        return Ok(SqlDecimal::ZERO);
+    }
+
+    let positive = bytes[0] & 0x80 != 0;
+    // infinity: 0xFF 0x65 and 0x00
+    if bytes == [0xFF, 0x65] {
+        return Err("Infinity".to_string());
+    } else if bytes == [0x00] {
+        return Err("-Infinity".to_string());
+    }
+
+    let (exponent, digits): (i32, Vec<i128>) = if positive {
+        (bytes[0] as i32 - 193, bytes[1..].iter().map(|d|*d as i128 - 1).collect())
+    } else {
+        (62 - bytes[0] as i32, bytes[1..].iter().take_while(|d|**d != 102).map(|d|101 - *d as i128).collect())
+    };
+
+    // mantissa with scale in decimal digits, digits which overflow mantissa are rounded
+    let mut mantissa: i128 = 0;
+    let mut scale = -2 * exponent - 2;
+    let mut rest = digits.iter();
+    for digit in rest.by_ref() {
+        mantissa = mantissa * 100 + digit;
+        scale += 2;
+        if mantissa >= 10i128.pow(34) {
+            break;
+        }
+    }
+    if rest.next().is_some_and(|digit|*digit >= 50) {
+        mantissa += 1;
+    }
+
+    if scale < 0 {
+        match 10i128.checked_pow(-scale as u32).and_then(|power|mantissa.checked_mul(power)) {
+            Some(value) if value <= DECIMAL_MAX_MANTISSA => mantissa = value,
+            _ => return Err(decimal_text(positive, exponent, &digits))
+        }
+        scale = 0;
+    }
+    while scale > DECIMAL_MAX_SCALE || mantissa > DECIMAL_MAX_MANTISSA {
+        if scale == 0 {
+            return Err(decimal_text(positive, exponent, &digits));
+        }
+        mantissa = (mantissa + 5) / 10;
+        scale -= 1;
+    }
+    while scale > 0 && mantissa % 10 == 0 {
+        mantissa /= 10;
+        scale -= 1;
+    }
+
+    Ok(SqlDecimal::from_i128_with_scale(if positive { mantissa } else { -mantissa }, scale as u32))
+}
+
+/// exact text of number by its exponent and base-100 digits, e.g. 1.5E+40 is 15 and 39 zeros
+fn decimal_text(positive: bool, exponent: i32, digits: &[i128]) -> String {
+    let mut text: String = digits.iter().map(|d|format!("{:02}", d)).collect();
+    // count of decimal digits before decimal point
+    let int_len = 2 * (exponent + 1);
+    if int_len <= 0 {
+        text.insert_str(0, &"0".repeat(-int_len as usize));
+        text.insert_str(0, "0.");
+    } else if int_len as usize >= text.len() {
+        text.push_str(&"0".repeat(int_len as usize - text.len()));
+    } else {
+        text.insert(int_len as usize, '.');
+    }
+
+    let mut text = text.trim_start_matches('0').to_string();
+    if text.contains('.') {
+        text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    if text.is_empty() || text.starts_with('.') {
+        text.insert(0, '0');
+    }
+    if !positive {
+        text.insert(0, '-');
+    }
+    text
+}
+
+fn decimal_to_row(source: &SqlDecimal, data: *mut u8) -> usize {
+    let mantissa = source.mantissa();
+    if mantissa == 0 {
+        unsafe { *data = 0x80; }
+        return 1;
+    }
+
+    // decimal digits are aligned by decimal point to pairs of base-100 digits
+    let mut text = mantissa.abs().to_string();
+    let mut scale = source.scale() as i32;
+    if scale % 2 != 0 {
+        text.push('0');
+        scale += 1;
+    }
+    if (text.len() as i32 - scale) % 2 != 0 {
+        text.insert(0, '0');
+    }
+    let exponent = (text.len() as i32 - scale) / 2 - 1;
+
+    let mut digits: Vec<u8> = text.as_bytes()
+        .chunks(2)
+        .map(|pair|(pair[0] - b'0') * 10 + (pair[1] - b'0'))
+        .collect();
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    let mut bytes = Vec::with_capacity(digits.len() + 2);
+    if mantissa > 0 {
+        bytes.push((193 + exponent) as u8);
+        bytes.extend(digits.iter().map(|d|d + 1));
+    } else {
+        bytes.push((62 - exponent) as u8);
+        bytes.extend(digits.iter().map(|d|101 - d));
+        if digits.len() < 20 {
+            bytes.push(102);
+        }
+    }
+
+    unsafe { ptr::copy(bytes.as_ptr(), data, bytes.len()); }
+    bytes.len()
+}
+
+// LOB and BFILE, buffer contains pointer to locator. fetched locator is copied, so it remains valid after next fetch.
+// binded locator replaces allocated one in buffer, so LOB must live until execution
+use crate::lob::{SqlLob, SqlBFile};
+
+impl From<ResultValue> for Option<SqlLob> {
+    fn from(v: ResultValue) -> Option<SqlLob> {
+        v.map(|valp, _| SqlLob::copy_of(descriptor_of(valp), oci::OCI_DTYPE_LOB)
+            .expect("Can not copy Oracle LOB locator"))
+    }
+}
+
+impl From<ResultValue> for Option<SqlBFile> {
+    fn from(v: ResultValue) -> Option<SqlBFile> {
+        v.map(|valp, _| SqlLob::copy_of(descriptor_of(valp), oci::OCI_DTYPE_FILE)
+            .map(SqlBFile::new)
+            .expect("Can not copy Oracle BFILE locator"))
+    }
+}
+
+impl ValueProjector<SqlLob> for SqlLob {
+    fn project_value(&self, projection: &mut ParamValue) {
+        projection.project(self, |data, _| {
+            unsafe { *(data as *mut *mut oci::OCILobLocator) = self.locator; }
+            0
+        });
+    }
+}
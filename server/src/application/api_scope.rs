@@ -1,10 +1,19 @@
 use std::sync::Arc;
-use actix_web::{get, web, Scope, Responder, HttpResponse};
+use actix_web::{get, post, put, patch, delete, guard, web, Scope, Responder, HttpResponse, HttpRequest};
+use actix_web::http::{header, StatusCode};
+use actix_web::web::Bytes;
+use actix_web::body::SizedStream;
+use futures::channel::{mpsc, oneshot};
+use futures::executor::{block_on, block_on_stream};
+use futures::{SinkExt, StreamExt};
 use serde::Deserialize;
+use serde_json::{Map, Value};
 
-use crate::application::{ApplicationState, query};
-use actix_web::http::header::ContentType;
-use std::collections::{HashMap, HashSet};
+use crate::application::{ApplicationState, query, filter, modification, rpc, lob, request_context, execute_blocking};
+use crate::datasource::RequestContext;
+use crate::application::encoding::{BinaryFormat, ResultFormat};
+use crate::metainfo as mi;
+use std::collections::HashSet;
 use crate::application;
 use actix_web::dev::HttpServiceFactory;
 
@@ -14,26 +23,66 @@ pub fn api_scope() -> impl HttpServiceFactory {
         .wrap(crate::security::Authorized::all())
         .service(table_query_by_pk)
         .service(table_query_by_params)
+        .service(lob_download)
+}
+
+// group of endpoints for modification of data, requires dedicated permission.
+// paths are shared with api scope, the scope is selected by http method, so it must be registered before api scope
+pub fn write_scope() -> impl HttpServiceFactory {
+    web::scope("/api")
+        .guard(guard::Any(guard::Post()).or(guard::Put()).or(guard::Patch()).or(guard::Delete()))
+        .wrap(crate::security::Authorized::writers())
+        .service(table_insert)
+        .service(table_update)
+        .service(table_update_partial)
+        .service(table_delete)
+        .service(lob_upload)
+}
+
+// group of endpoints for calls of procedures, requires dedicated permission.
+// it must be registered before write and api scopes, its prefix overlaps paths of tables
+pub fn rpc_scope() -> impl HttpServiceFactory {
+    web::scope("/api/schemas/{schema}/rpc")
+        .guard(guard::Post())
+        .wrap(crate::security::Authorized::callers())
+        .service(package_call)
+        .service(procedure_call)
+}
+
+/// count of LOB chunks buffered between oracle session and http body
+const LOB_CHANNEL_SIZE: usize = 4;
+
+#[derive(Deserialize)]
+struct SelectParams {
+    select: Option<String>,
+    format: Option<String>,
+    expand: Option<String>,
 }
 
 #[get("/schemas/{schema}/{table}/{pk}")]
-async fn table_query_by_pk(path: web::Path<(String,String,String)>, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+async fn table_query_by_pk(path: web::Path<(String,String,String)>, req: web::Query<SelectParams>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
     let (schema_name,table_name, pk_params) = path.into_inner();
     let metainfo = data.metainfo.read().unwrap();
 
     if let Some(info) = metainfo.schemas.get(schema_name.as_str()) {
         if let Some(info) = info.tables.get(table_name.as_str()) {
             let pk_params: Vec<String> = pk_params.split(",").map(|s|s.to_string()).collect();
-            let query = query::DynamicQuery::create_from_pk(&schema_name, info, pk_params);
+            let format = match ResultFormat::negotiate(&http_req, &req.format) {
+                Ok(format) => format,
+                Err(err) => return HttpResponse::BadRequest().body(err)
+            };
+            let select = split_list(&req.select);
+            let expand = split_list(&req.expand);
+            let context = request_context(&http_req, &data);
+            let query = query::DynamicQuery::create_from_pk(&schema_name, info, pk_params, select, data.formats.binary)
+                .and_then(|query|query.with_format(format).with_expand(&metainfo, &schema_name, info, expand))
+                .map(|query|query.with_context(context.clone()));
             return match query {
                 Ok(query) => {
-                    let result = web::block(move || query.fetch_one()).await;
+                    let result = execute_blocking(&context, move || query.fetch_one()).await;
                     match result {
-                        Ok(result) => HttpResponse::Ok().set(ContentType::json()).body(result),
-                        Err(e) => {
-                            eprintln!("{:?}",e);
-                            HttpResponse::InternalServerError().finish()
-                        }
+                        Ok(result) => HttpResponse::Ok().content_type(format.content_type()).body(result),
+                        Err(e) => e.response()
                     }
                 },
                 Err(err) => HttpResponse::BadRequest().body(err)
@@ -48,41 +97,281 @@ async fn table_query_by_pk(path: web::Path<(String,String,String)>, data: web::D
 
 #[derive(Deserialize)]
 struct QueryParams {
-    q:      String,
+    q:      Option<String>,
+    select: Option<String>,
     limit:  Option<u16>,
     offset: Option<u16>,
     order:  Option<String>,
+    cursor: Option<String>,
+    count:  Option<String>,
+    format: Option<String>,
+    expand: Option<String>,
 }
 
 #[get("/schemas/{schema}/{table}/")]
-async fn table_query_by_params(path: web::Path<(String,String)>, req: web::Query<QueryParams>, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+async fn table_query_by_params(path: web::Path<(String,String)>, req: web::Query<QueryParams>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
     let (schema_name,table_name) = path.into_inner();
     let metainfo = data.metainfo.read().unwrap();
 
     if let Some(info) = metainfo.schemas.get(schema_name.as_str()) {
         if let Some(info) = info.tables.get(table_name.as_str()) {
-            // println!("{}.{}; q: {}", schema_name, table_name, req.q);
-
-            let q: serde_json::error::Result<HashMap<String,String>> = serde_json::from_str(&req.q);
-            return match q {
-                Ok(paremeters) => {
-                    let order: Vec<String> = req.order.as_ref().map(|s|s.split(",").map(|s|s.to_string()).collect()).unwrap_or(vec![]);
-                    let query = query::DynamicQuery::create_from_params(&schema_name, info, paremeters, order, req.limit, req.offset);
-                    return match query {
-                        Ok(query) => {
-                            let result = web::block(move || query.fetch_many()).await;
-                            match result {
-                                Ok(result) => HttpResponse::Ok().set(ContentType::json()).body(result),
-                                Err(e) => {
-                                    eprintln!("{:?}",e);
-                                    HttpResponse::InternalServerError().finish()
-                                }
+            // println!("{}.{}; q: {:?}", schema_name, table_name, req.q);
+
+            let predicate = match parse_filter(info, &req.q, data.formats.binary) {
+                Ok(predicate) => predicate,
+                Err(err) => return HttpResponse::BadRequest().body(err)
+            };
+            let count = match req.count.as_deref().map(query::CountMode::parse).transpose() {
+                Ok(count) => count,
+                Err(err) => return HttpResponse::BadRequest().body(err)
+            };
+            let format = match ResultFormat::negotiate(&http_req, &req.format) {
+                Ok(format) => format,
+                Err(err) => return HttpResponse::BadRequest().body(err)
+            };
+            let paging = query::Paging { limit: req.limit, offset: req.offset, cursor: req.cursor.clone(), count };
+            let select = split_list(&req.select);
+            let order = split_list(&req.order);
+            let expand = split_list(&req.expand);
+            let context = request_context(&http_req, &data);
+            let query = query::DynamicQuery::create_from_params(&schema_name, info, predicate, select, order, paging)
+                .and_then(|query|query.with_format(format).with_expand(&metainfo, &schema_name, info, expand))
+                .map(|query|query.with_context(context.clone()));
+            return match query {
+                Ok(query) => {
+                    let result = execute_blocking(&context, move || query.fetch_many()).await;
+                    match result {
+                        Ok(page) => {
+                            // count of rows and next page are duplicated in headers for CSV, which has no envelope
+                            let mut response = HttpResponse::Ok();
+                            response.content_type(format.content_type());
+                            if let Some(total) = page.total {
+                                response.header("X-Total-Count", total.to_string());
                             }
+                            if let Some(next) = page.next {
+                                response.header("X-Next-Page", next);
+                            }
+                            response.body(page.body)
                         },
-                        Err(err) => HttpResponse::BadRequest().body(err)
-                    };
+                        Err(e) => e.response()
+                    }
                 },
-                Err(err) => HttpResponse::BadRequest().body(format!("Invalid query format: {}", err))
+                Err(err) => HttpResponse::BadRequest().body(err)
+            };
+        }
+    };
+
+    HttpResponse::NotFound().finish()
+}
+
+#[post("/schemas/{schema}/{table}/")]
+async fn table_insert(path: web::Path<(String,String)>, body: web::Json<Map<String,Value>>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let (schema_name,table_name) = path.into_inner();
+    let metainfo = data.metainfo.read().unwrap();
+
+    if let Some(info) = metainfo.schemas.get(schema_name.as_str()) {
+        if let Some(info) = info.tables.get(table_name.as_str()) {
+            let statement = modification::DynamicModification::create_insert(&schema_name, info, body.into_inner(), data.formats.binary);
+            return execute_modification(statement, request_context(&http_req, &data), StatusCode::CREATED).await;
+        }
+    };
+
+    HttpResponse::NotFound().finish()
+}
+
+#[put("/schemas/{schema}/{table}/{pk}")]
+async fn table_update(path: web::Path<(String,String,String)>, body: web::Json<Map<String,Value>>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    update_by_pk(path.into_inner(), body.into_inner(), request_context(&http_req, &data), data, true).await
+}
+
+#[patch("/schemas/{schema}/{table}/{pk}")]
+async fn table_update_partial(path: web::Path<(String,String,String)>, body: web::Json<Map<String,Value>>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    update_by_pk(path.into_inner(), body.into_inner(), request_context(&http_req, &data), data, false).await
+}
+
+#[delete("/schemas/{schema}/{table}/{pk}")]
+async fn table_delete(path: web::Path<(String,String,String)>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let (schema_name,table_name, pk_params) = path.into_inner();
+    let metainfo = data.metainfo.read().unwrap();
+
+    if let Some(info) = metainfo.schemas.get(schema_name.as_str()) {
+        if let Some(info) = info.tables.get(table_name.as_str()) {
+            let pk_params: Vec<String> = pk_params.split(",").map(|s|s.to_string()).collect();
+            let statement = modification::DynamicModification::create_delete(&schema_name, info, pk_params, data.formats.binary);
+            return execute_modification(statement, request_context(&http_req, &data), StatusCode::NO_CONTENT).await;
+        }
+    };
+
+    HttpResponse::NotFound().finish()
+}
+
+/// stream content of LOB column of row as file, content type is detected by first bytes of content
+#[get("/schemas/{schema}/{table}/{pk}/{column}")]
+async fn lob_download(path: web::Path<(String,String,String,String)>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let (schema_name, table_name, pk_params, column_name) = path.into_inner();
+    let column = match lob_column(&schema_name, &table_name, &pk_params, &column_name, &data) {
+        Some(Ok(column)) => column.with_context(request_context(&http_req, &data)),
+        Some(Err(err)) => return HttpResponse::BadRequest().body(err),
+        None => return HttpResponse::NotFound().finish()
+    };
+
+    // streaming is not limited by execution time of route, it is timeout of each call
+    let (head_tx, head_rx) = oneshot::channel::<Option<lob::LobHead>>();
+    // error of channel is sent from blocking thread, so it is converted to actix error in response
+    let (mut tx, rx) = mpsc::channel::<Result<Bytes, String>>(LOB_CHANNEL_SIZE);
+    let mut chunks_tx = tx.clone();
+
+    actix_web::rt::spawn(async move {
+        let result = web::block(move || {
+            column.download(|head| { let _ = head_tx.send(head); },
+                            |chunk| block_on(chunks_tx.send(Ok(Bytes::from(chunk)))).is_ok())
+        }).await;
+
+        if let Err(e) = result {
+            // response may be already started, error aborts it
+            eprintln!("{:?}",e);
+            let _ = tx.send(Err("Download of LOB failed".to_string())).await;
+        }
+    });
+
+    let rx = rx.map(|chunk|chunk.map_err(actix_web::error::ErrorInternalServerError));
+    match head_rx.await {
+        Ok(Some(head)) => {
+            let filename: String = format!("{}_{}.{}", table_name, column_name, head.extension)
+                .chars()
+                .map(|c|if c.is_ascii_alphanumeric() || c == '_' || c == '.' { c } else { '_' })
+                .collect();
+            let mut response = HttpResponse::Ok();
+            response.content_type(head.content_type);
+            response.header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename));
+            match head.length {
+                Some(length) => response.body(SizedStream::new(length, rx)),
+                None => response.streaming(rx)
+            }
+        },
+        // row is not found or LOB is NULL
+        Ok(None) => HttpResponse::NotFound().finish(),
+        // download is failed before first chunk
+        Err(_) => HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// replace content of LOB column of row by request body, body is written to LOB by chunks while it is received
+#[put("/schemas/{schema}/{table}/{pk}/{column}")]
+async fn lob_upload(path: web::Path<(String,String,String,String)>, mut body: web::Payload, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let (schema_name, table_name, pk_params, column_name) = path.into_inner();
+    let context = request_context(&http_req, &data);
+    let column = match lob_column(&schema_name, &table_name, &pk_params, &column_name, &data) {
+        Some(Ok(column)) => column.with_context(context.clone()),
+        Some(Err(err)) => return HttpResponse::BadRequest().body(err),
+        None => return HttpResponse::NotFound().finish()
+    };
+
+    // bounded channel: body is read while previous chunks are written, upload is stopped if writing fails
+    let (mut tx, rx) = mpsc::channel::<Result<Bytes, String>>(LOB_CHANNEL_SIZE);
+    let feed = async move {
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(|err|format!("Can not read request body: {}", err));
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    };
+    let upload = execute_blocking(&context, move || column.upload(block_on_stream(rx)));
+
+    let (_, result) = futures::join!(feed, upload);
+    match result {
+        Ok(Some(_)) => HttpResponse::NoContent().finish(),
+        // row is not found
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(e) => e.response()
+    }
+}
+
+#[post("/{package}/{procedure}")]
+async fn package_call(path: web::Path<(String,String,String)>, body: web::Json<Map<String,Value>>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let (schema_name, package, name) = path.into_inner();
+    call_procedure(schema_name, Some(package), name, body.into_inner(), request_context(&http_req, &data), data).await
+}
+
+#[post("/{procedure}")]
+async fn procedure_call(path: web::Path<(String,String)>, body: web::Json<Map<String,Value>>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let (schema_name, name) = path.into_inner();
+    call_procedure(schema_name, None, name, body.into_inner(), request_context(&http_req, &data), data).await
+}
+
+/// parse `q` parameter (json) to filter predicate, absent parameter is empty filter
+pub(super) fn parse_filter(info: &mi::TableInfo, q: &Option<String>, binary: BinaryFormat) -> Result<filter::Predicate, String> {
+    let q: Value = serde_json::from_str(q.as_deref().unwrap_or("{}"))
+        .map_err(|err|format!("Invalid query format: {}", err))?;
+    filter::Predicate::parse(info, &q, binary)
+}
+
+/// split comma-separated list of query parameter, empty list if parameter is absent
+pub(super) fn split_list(param: &Option<String>) -> Vec<String> {
+    param.as_ref().map(|s|s.split(",").map(|s|s.to_string()).collect()).unwrap_or(vec![])
+}
+
+/// LOB column of row by primary key, None if table is not found.
+/// metainfo is not locked while LOB is streamed
+fn lob_column(schema_name: &str, table_name: &str, pk_params: &str, column_name: &str, data: &ApplicationState) -> Option<Result<lob::LobColumn, String>> {
+    let metainfo = data.metainfo.read().unwrap();
+    let info = metainfo.schemas.get(schema_name)?.tables.get(table_name)?;
+    let pk_params: Vec<String> = pk_params.split(",").map(|s|s.to_string()).collect();
+    Some(lob::LobColumn::create(schema_name, info, pk_params, column_name, data.formats.binary))
+}
+
+/// full (PUT) or partial (PATCH) update of row by primary key
+async fn update_by_pk(path: (String,String,String), values: Map<String,Value>, context: RequestContext, data: web::Data<Arc<ApplicationState>>, full: bool) -> HttpResponse {
+    let (schema_name,table_name, pk_params) = path;
+    let metainfo = data.metainfo.read().unwrap();
+
+    if let Some(info) = metainfo.schemas.get(schema_name.as_str()) {
+        if let Some(info) = info.tables.get(table_name.as_str()) {
+            let pk_params: Vec<String> = pk_params.split(",").map(|s|s.to_string()).collect();
+            let statement = modification::DynamicModification::create_update(&schema_name, info, pk_params, values, full, data.formats.binary);
+            return execute_modification(statement, context, StatusCode::NO_CONTENT).await;
+        }
+    };
+
+    HttpResponse::NotFound().finish()
+}
+
+async fn execute_modification(statement: Result<modification::DynamicModification, String>, context: RequestContext, status: StatusCode) -> HttpResponse {
+    match statement {
+        Ok(statement) => {
+            let statement = statement.with_context(context.clone());
+            let result = execute_blocking(&context, move || statement.execute()).await;
+            match result {
+                // update or delete of absent row
+                Ok(result) if result.row_count == 0 => HttpResponse::NotFound().finish(),
+                Ok(result) => match result.returned {
+                    Some(body) => HttpResponse::build(status).content_type("application/json").body(body),
+                    None => HttpResponse::build(status).finish()
+                },
+                Err(e) => e.response()
+            }
+        },
+        Err(err) => HttpResponse::BadRequest().body(err)
+    }
+}
+
+/// call of standalone or packaged procedure, arguments are members of json body
+async fn call_procedure(schema_name: String, package: Option<String>, name: String, values: Map<String,Value>, context: RequestContext, data: web::Data<Arc<ApplicationState>>) -> HttpResponse {
+    let metainfo = data.metainfo.read().unwrap();
+
+    if let Some(info) = metainfo.schemas.get(schema_name.as_str()) {
+        if info.find_procedures(package.as_deref(), &name).next().is_some() {
+            return match rpc::DynamicCall::create(&schema_name, info, package.as_deref(), &name, values, data.formats.binary) {
+                Ok(call) => {
+                    let call = call.with_context(context.clone());
+                    let result = execute_blocking(&context, move || call.execute()).await;
+                    match result {
+                        Ok(result) => HttpResponse::Ok().content_type("application/json").body(result),
+                        Err(e) => e.response()
+                    }
+                },
+                Err(err) => HttpResponse::BadRequest().body(err)
             };
         }
     };
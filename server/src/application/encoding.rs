@@ -0,0 +1,620 @@
+use std::sync::Arc;
+use actix_web::HttpRequest;
+use actix_web::http::header;
+use serde::{Serialize, Serializer};
+use serde::ser::{Error, SerializeMap};
+use serde_json::value::RawValue;
+use chrono::{DateTime, Duration, Local, NaiveDateTime};
+use crate::utils::{hex_encode, hex_decode};
+
+/// Format of query result, negotiated by `Accept` header or `format` parameter
+#[derive(Clone, Copy, PartialEq)]
+pub enum ResultFormat {
+    Json, Csv, Xml
+}
+
+impl ResultFormat {
+    pub fn parse(value: &str) -> Result<ResultFormat, String> {
+        match value {
+            "json" => Ok(ResultFormat::Json),
+            "csv" => Ok(ResultFormat::Csv),
+            "xml" => Ok(ResultFormat::Xml),
+            _ => Err(format!("Invalid format '{}', must be json, csv or xml", value))
+        }
+    }
+
+    /// `format` parameter overrides `Accept` header, json is default
+    pub fn negotiate(req: &HttpRequest, format: &Option<String>) -> Result<ResultFormat, String> {
+        if let Some(format) = format {
+            return ResultFormat::parse(format);
+        }
+
+        let accept = req.headers()
+            .get(header::ACCEPT)
+            .and_then(|value|value.to_str().ok())
+            .unwrap_or("");
+
+        if accept.contains("text/csv") {
+            Ok(ResultFormat::Csv)
+        } else if accept.contains("application/xml") || accept.contains("text/xml") {
+            Ok(ResultFormat::Xml)
+        } else {
+            Ok(ResultFormat::Json)
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResultFormat::Json => "application/json",
+            ResultFormat::Csv => "text/csv; charset=utf-8",
+            ResultFormat::Xml => "application/xml; charset=utf-8",
+        }
+    }
+}
+
+/// JSON representation of exact decimals: numbers, or strings for clients which parse numbers as floats
+#[derive(Clone, Copy, PartialEq)]
+pub enum DecimalFormat {
+    Number, String
+}
+
+impl DecimalFormat {
+    pub fn parse(value: &str) -> Result<DecimalFormat, String> {
+        match value {
+            "number" => Ok(DecimalFormat::Number),
+            "string" => Ok(DecimalFormat::String),
+            _ => Err(format!("Invalid decimal format '{}', must be number or string", value))
+        }
+    }
+}
+
+/// Text representation of RAW values: lowercase hex, or base64url without padding, which is safe in urls
+#[derive(Clone, Copy, PartialEq)]
+pub enum BinaryFormat {
+    Hex, Base64
+}
+
+impl BinaryFormat {
+    pub fn parse(value: &str) -> Result<BinaryFormat, String> {
+        match value {
+            "hex" => Ok(BinaryFormat::Hex),
+            "base64" => Ok(BinaryFormat::Base64),
+            _ => Err(format!("Invalid binary format '{}', must be hex or base64", value))
+        }
+    }
+
+    pub fn format(self, value: &[u8]) -> String {
+        match self {
+            BinaryFormat::Hex => hex_encode(value),
+            BinaryFormat::Base64 => base64::encode_config(value, base64::URL_SAFE_NO_PAD)
+        }
+    }
+
+    /// binary value in this format, base64 is accepted with standard or url alphabet and padding
+    pub fn parse_value(self, value: &str) -> Result<Vec<u8>, &'static str> {
+        match self {
+            BinaryFormat::Hex => hex_decode(value).map_err(|_|"Invalid hex binary value"),
+            BinaryFormat::Base64 => {
+                let value: String = value.trim_end_matches('=').chars().map(|c|match c { '+' => '-', '/' => '_', c => c }).collect();
+                base64::decode_config(&value, base64::URL_SAFE_NO_PAD).map_err(|_|"Invalid base64 binary value")
+            }
+        }
+    }
+}
+
+/// Formats of values in results and parameters, they are configured on startup.
+/// decimals are numbers and binary values are hex by default
+#[derive(Clone, Copy)]
+pub struct ValueFormats {
+    pub decimal: DecimalFormat,
+    pub binary:  BinaryFormat,
+}
+
+impl Default for ValueFormats {
+    fn default() -> Self {
+        ValueFormats { decimal: DecimalFormat::Number, binary: BinaryFormat::Hex }
+    }
+}
+
+/// binary values in texts which are parsed back by server: cursors and keys of expanded rows
+pub const INTERNAL_BINARY: BinaryFormat = BinaryFormat::Hex;
+
+/// Typed value of column in result row
+#[derive(Clone)]
+pub enum ColumnValue {
+    Null,
+    Int16(i16),
+    Int32(i32),
+    Int64(i64),
+    Float64(f64),
+    Decimal(oracle::SqlDecimal),
+    Varchar(String),
+    /// RAW and LONG RAW
+    Raw(Vec<u8>),
+    /// DATE in local time zone
+    DateTime(oracle::SqlDateTime),
+    /// TIMESTAMP without time zone, it is naive ISO 8601 value without offset
+    Timestamp(oracle::SqlTimestamp),
+    /// TIMESTAMP WITH TIME ZONE and TIMESTAMP WITH LOCAL TIME ZONE
+    TimestampTz(oracle::SqlTimestampTz),
+    IntervalYM(oracle::SqlIntervalYM),
+    IntervalDS(oracle::SqlIntervalDS),
+    /// LOB columns, they are read by their own endpoint
+    Unsupported,
+    /// expanded parent row: names and values of columns
+    Object(Arc<Vec<String>>, Vec<ColumnValue>),
+    /// expanded child rows: names of columns and values of rows
+    Array(Arc<Vec<String>>, Vec<Vec<ColumnValue>>),
+}
+
+impl ColumnValue {
+    pub fn from_result(value: &oracle::ResultValue, col_type: oracle::SqlType) -> ColumnValue {
+        if let oracle::ResultValue::Nil = value {
+            return ColumnValue::Null;
+        }
+
+        let value = *value;
+        match col_type {
+            oracle::SqlType::Int16 => ColumnValue::Int16(value.into()),
+            oracle::SqlType::Int32 => ColumnValue::Int32(value.into()),
+            oracle::SqlType::Int64 => ColumnValue::Int64(value.into()),
+            oracle::SqlType::Float64 => ColumnValue::Float64(value.into()),
+            // number out of range of Decimal is its exact text
+            oracle::SqlType::Decimal => match value.into() {
+                Ok(val) => ColumnValue::Decimal(val),
+                Err(text) => ColumnValue::Varchar(text)
+            },
+            oracle::SqlType::Varchar | oracle::SqlType::Long => ColumnValue::Varchar(value.into()),
+            oracle::SqlType::Raw | oracle::SqlType::LongRaw => ColumnValue::Raw(value.into()),
+            oracle::SqlType::DateTime => ColumnValue::DateTime(value.into()),
+            oracle::SqlType::Timestamp => ColumnValue::Timestamp(value.into()),
+            oracle::SqlType::TimestampTz | oracle::SqlType::TimestampLtz => ColumnValue::TimestampTz(value.into()),
+            oracle::SqlType::IntervalYM => ColumnValue::IntervalYM(value.into()),
+            oracle::SqlType::IntervalDS => ColumnValue::IntervalDS(value.into()),
+            oracle::SqlType::Blob | oracle::SqlType::Clob | oracle::SqlType::NClob | oracle::SqlType::BFile |
+            oracle::SqlType::Unsupported => ColumnValue::Unsupported,
+        }
+    }
+
+    /// text representation of value with binary values in format, None for NULL.
+    /// it is used in CSV, XML and cursors, and must be parsed back by ParsedParameter::parse
+    pub fn to_text(&self, binary: BinaryFormat) -> Option<String> {
+        match self {
+            ColumnValue::Null | ColumnValue::Unsupported => None,
+            ColumnValue::Object(..) | ColumnValue::Array(..) => None,
+            ColumnValue::Int16(val) => Some(val.to_string()),
+            ColumnValue::Int32(val) => Some(val.to_string()),
+            ColumnValue::Int64(val) => Some(val.to_string()),
+            ColumnValue::Float64(val) => Some(val.to_string()),
+            ColumnValue::Decimal(val) => Some(val.to_string()),
+            ColumnValue::Varchar(val) => Some(val.to_owned()),
+            ColumnValue::Raw(val) => Some(binary.format(val)),
+            ColumnValue::DateTime(val) => Some(val.to_rfc3339()),
+            ColumnValue::Timestamp(val) => Some(format_timestamp(val)),
+            ColumnValue::TimestampTz(val) => Some(val.to_rfc3339()),
+            ColumnValue::IntervalYM(val) => Some(format_interval_ym(val)),
+            ColumnValue::IntervalDS(val) => Some(format_interval_ds(val)),
+        }
+    }
+}
+
+/// ISO 8601 format of TIMESTAMP without time zone: 2020-12-23T10:15:30.500, fraction of second is optional
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+pub fn format_timestamp(value: &oracle::SqlTimestamp) -> String {
+    value.format(TIMESTAMP_FORMAT).to_string()
+}
+
+/// naive ISO 8601 timestamp, RFC 3339 value with offset is converted to local time zone
+pub fn parse_timestamp(value: &str) -> Result<oracle::SqlTimestamp, &'static str> {
+    match NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT) {
+        Ok(val) => Ok(val),
+        Err(_) => DateTime::parse_from_rfc3339(value)
+            .map(|val|val.with_timezone(&Local).naive_local())
+            .map_err(|_|"Invalid ISO 8601 timestamp value")
+    }
+}
+
+// intervals are ISO 8601 durations: -P1Y2M for INTERVAL YEAR TO MONTH, P1DT2H3M4.5S for INTERVAL DAY TO SECOND
+
+const INVALID_DURATION: &str = "Invalid ISO 8601 duration value";
+
+pub fn format_interval_ym(value: &oracle::SqlIntervalYM) -> String {
+    let sign = if value.years < 0 || value.months < 0 { "-" } else { "" };
+    format!("{}P{}Y{}M", sign, value.years.abs(), value.months.abs())
+}
+
+pub fn format_interval_ds(value: &oracle::SqlIntervalDS) -> String {
+    let sign = if *value < Duration::zero() { "-" } else { "" };
+    let secs = value.num_seconds().abs();
+    let nanos = (*value - Duration::seconds(value.num_seconds())).num_nanoseconds().unwrap_or(0).abs();
+
+    let mut text = format!("{}P{}DT{}H{}M{}", sign, secs / 86400, secs % 86400 / 3600, secs % 3600 / 60, secs % 60);
+    if nanos > 0 {
+        text.push_str(format!(".{:09}", nanos).trim_end_matches('0'));
+    }
+    text.push('S');
+    text
+}
+
+pub fn parse_interval_ym(value: &str) -> Result<oracle::SqlIntervalYM, &'static str> {
+    let (negative, parts) = duration_parts(value)?;
+    let mut interval = oracle::SqlIntervalYM::default();
+
+    for (number, designator, time) in parts {
+        let number: i32 = number.parse().map_err(|_|INVALID_DURATION)?;
+        match (designator, time) {
+            ('Y', false) => interval.years = number,
+            ('M', false) => interval.months = number,
+            _ => return Err("INTERVAL YEAR TO MONTH has only years and months")
+        }
+    }
+
+    if negative {
+        interval.years = -interval.years;
+        interval.months = -interval.months;
+    }
+    Ok(interval)
+}
+
+pub fn parse_interval_ds(value: &str) -> Result<oracle::SqlIntervalDS, &'static str> {
+    let (negative, parts) = duration_parts(value)?;
+    let mut interval = Duration::zero();
+
+    for (number, designator, time) in parts {
+        let part = match (designator, time) {
+            ('S', true) => {
+                let (secs, fraction) = number.split_at(number.find('.').unwrap_or(number.len()));
+                let secs: i64 = secs.parse().map_err(|_|INVALID_DURATION)?;
+                let nanos = match fraction.get(1..) {
+                    Some(digits) if !digits.is_empty() && digits.len() <= 9 => {
+                        let nanos: i64 = digits.parse().map_err(|_|INVALID_DURATION)?;
+                        nanos * 10i64.pow(9 - digits.len() as u32)
+                    },
+                    Some(_) => return Err(INVALID_DURATION),
+                    None => 0
+                };
+                Duration::seconds(secs) + Duration::nanoseconds(nanos)
+            },
+            (designator, time) => {
+                let number: i64 = number.parse().map_err(|_|INVALID_DURATION)?;
+                match (designator, time) {
+                    ('D', false) => Duration::days(number),
+                    ('H', true) => Duration::hours(number),
+                    ('M', true) => Duration::minutes(number),
+                    _ => return Err("INTERVAL DAY TO SECOND has only days, hours, minutes and seconds")
+                }
+            }
+        };
+        interval = interval + part;
+    }
+
+    Ok(if negative { -interval } else { interval })
+}
+
+/// split duration to numbers with designators, which are marked as parts of time after `T`
+fn duration_parts(value: &str) -> Result<(bool, Vec<(&str, char, bool)>), &'static str> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value)
+    };
+    let mut rest = value.strip_prefix('P').ok_or(INVALID_DURATION)?;
+    let mut parts = Vec::new();
+    let mut time = false;
+
+    while !rest.is_empty() {
+        if let Some(value) = rest.strip_prefix('T') {
+            time = true;
+            rest = value;
+            continue;
+        }
+        let len = rest.find(|c: char|!c.is_ascii_digit() && c != '.').ok_or(INVALID_DURATION)?;
+        let designator = rest[len..].chars().next().unwrap();
+        if len == 0 || !designator.is_ascii_uppercase() {
+            return Err(INVALID_DURATION);
+        }
+        parts.push((&rest[..len], designator, time));
+        rest = &rest[len + 1..];
+    }
+
+    if parts.is_empty() {
+        return Err(INVALID_DURATION);
+    }
+    Ok((negative, parts))
+}
+
+/// Value serialized as JSON in configured formats
+struct JsonValue<'a> {
+    value:   &'a ColumnValue,
+    formats: ValueFormats,
+}
+
+impl<'a> Serialize for JsonValue<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let formats = self.formats;
+        match self.value {
+            ColumnValue::Null | ColumnValue::Unsupported => serializer.serialize_none(),
+            ColumnValue::Int16(val) => serializer.serialize_i16(*val),
+            ColumnValue::Int32(val) => serializer.serialize_i32(*val),
+            ColumnValue::Int64(val) => serializer.serialize_i64(*val),
+            ColumnValue::Float64(val) => serializer.serialize_f64(*val),
+            ColumnValue::Decimal(val) if formats.decimal == DecimalFormat::String => serializer.serialize_str(&val.to_string()),
+            ColumnValue::Decimal(val) => raw_decimal(val).map_err(S::Error::custom)?.serialize(serializer),
+            ColumnValue::Varchar(val) => serializer.serialize_str(val),
+            ColumnValue::Raw(val) => serializer.serialize_str(&formats.binary.format(val)),
+            ColumnValue::DateTime(val) => serializer.serialize_str(&val.to_rfc3339()),
+            ColumnValue::Timestamp(val) => serializer.serialize_str(&format_timestamp(val)),
+            ColumnValue::TimestampTz(val) => serializer.serialize_str(&val.to_rfc3339()),
+            ColumnValue::IntervalYM(val) => serializer.serialize_str(&format_interval_ym(val)),
+            ColumnValue::IntervalDS(val) => serializer.serialize_str(&format_interval_ds(val)),
+            ColumnValue::Object(names, values) => JsonRow { names, values, formats }.serialize(serializer),
+            ColumnValue::Array(names, rows) => serializer.collect_seq(rows.iter().map(|values|JsonRow { names, values, formats })),
+        }
+    }
+}
+
+/// decimal as JSON number with all its digits: its text is written as is, it is not converted to f64.
+/// text of decimal is valid JSON number, it has no exponent
+fn raw_decimal(value: &oracle::SqlDecimal) -> Result<Box<RawValue>, serde_json::Error> {
+    RawValue::from_string(value.to_string())
+}
+
+/// Row serialized as JSON object, values without names (hidden columns) are skipped
+struct JsonRow<'a> {
+    names:   &'a [String],
+    values:  &'a [ColumnValue],
+    formats: ValueFormats,
+}
+
+impl<'a> Serialize for JsonRow<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.names.len()))?;
+        for (name, value) in self.names.iter().zip(self.values.iter()) {
+            map.serialize_entry(name, &JsonValue { value, formats: self.formats })?;
+        }
+        map.end()
+    }
+}
+
+/// write row in result format to buffer, values are in configured formats
+pub fn write_row(buf: &mut Vec<u8>, format: ResultFormat, formats: ValueFormats, names: &[String], values: &[ColumnValue]) {
+    match format {
+        ResultFormat::Json => write_json(buf, &JsonRow { names, values, formats }),
+        ResultFormat::Csv => write_csv_row(buf, formats.binary, names.len(), values),
+        ResultFormat::Xml => write_xml_row(buf, formats.binary, names, values),
+    }
+}
+
+/// write serializable value as JSON to buffer.
+/// serialization to memory can not fail: keys are strings and buffer is growing
+pub fn write_json<T: Serialize>(buf: &mut Vec<u8>, value: &T) {
+    serde_json::to_writer(buf, value).unwrap()
+}
+
+// CSV (RFC 4180): fields separated by comma, rows by CRLF, NULL is empty field
+
+pub fn write_csv_header(buf: &mut Vec<u8>, names: &[String]) {
+    for (idx, name) in names.iter().enumerate() {
+        if idx > 0 {
+            buf.push(b',');
+        }
+        write_csv_field(buf, name);
+    }
+    buf.extend_from_slice(b"\r\n");
+}
+
+fn write_csv_row(buf: &mut Vec<u8>, binary: BinaryFormat, len: usize, values: &[ColumnValue]) {
+    for (idx, value) in values.iter().take(len).enumerate() {
+        if idx > 0 {
+            buf.push(b',');
+        }
+        if let Some(text) = value.to_text(binary) {
+            write_csv_field(buf, &text);
+        }
+    }
+    buf.extend_from_slice(b"\r\n");
+}
+
+/// quote field if it contains separator, quote or line break; quotes are doubled
+fn write_csv_field(buf: &mut Vec<u8>, value: &str) {
+    if value.contains(|c: char|c == ',' || c == '"' || c == '\r' || c == '\n') {
+        buf.push(b'"');
+        buf.extend_from_slice(value.replace('"', "\"\"").as_bytes());
+        buf.push(b'"');
+    } else {
+        buf.extend_from_slice(value.as_bytes());
+    }
+}
+
+// XML: <rows><row><name>value</name>...</row></rows>, NULL is empty element with nil attribute,
+// characters of column names not allowed in element names (like `$` or `#`) are encoded as `_xHHHH_`
+
+pub const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+
+fn write_xml_row(buf: &mut Vec<u8>, binary: BinaryFormat, names: &[String], values: &[ColumnValue]) {
+    buf.extend_from_slice(b"<row>");
+    write_xml_fields(buf, binary, names, values);
+    buf.extend_from_slice(b"</row>");
+}
+
+/// expanded parent row is nested element with fields, expanded child rows are nested `row` elements
+fn write_xml_fields(buf: &mut Vec<u8>, binary: BinaryFormat, names: &[String], values: &[ColumnValue]) {
+    for (name, value) in names.iter().zip(values.iter()) {
+        match value {
+            ColumnValue::Object(names, values) => {
+                write_xml_tag(buf, "<", name, ">");
+                write_xml_fields(buf, binary, names, values);
+                write_xml_tag(buf, "</", name, ">");
+            },
+            ColumnValue::Array(names, rows) => {
+                write_xml_tag(buf, "<", name, ">");
+                for values in rows {
+                    write_xml_row(buf, binary, names, values);
+                }
+                write_xml_tag(buf, "</", name, ">");
+            },
+            _ => match value.to_text(binary) {
+                Some(text) => {
+                    write_xml_tag(buf, "<", name, ">");
+                    buf.extend_from_slice(&quick_xml::escape::escape(text.as_bytes()));
+                    write_xml_tag(buf, "</", name, ">");
+                },
+                None => write_xml_tag(buf, "<", name, " nil=\"true\"/>")
+            }
+        }
+    }
+}
+
+fn write_xml_tag(buf: &mut Vec<u8>, open: &str, name: &str, close: &str) {
+    buf.extend_from_slice(open.as_bytes());
+    let mut chars = name.chars().peekable();
+    let mut first = true;
+    while let Some(c) = chars.next() {
+        let valid = c.is_alphabetic() || c == '_' || (!first && (c.is_numeric() || c == '-' || c == '.'));
+        // literal `_x` is encoded too, so that the encoding can be reversed
+        if valid && !(c == '_' && chars.peek() == Some(&'x')) {
+            let mut bytes = [0; 4];
+            buf.extend_from_slice(c.encode_utf8(&mut bytes).as_bytes());
+        } else {
+            buf.extend_from_slice(format!("_x{:04X}_", c as u32).as_bytes());
+        }
+        first = false;
+    }
+    buf.extend_from_slice(close.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name|name.to_string()).collect()
+    }
+
+    fn write(format: ResultFormat, names: &[String], values: &[ColumnValue]) -> String {
+        let mut buf = Vec::new();
+        write_row(&mut buf, format, ValueFormats::default(), names, values);
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let mut buf = Vec::new();
+        write_csv_header(&mut buf, &names(&["id", "a,b"]));
+        assert_eq!(String::from_utf8(buf).unwrap(), "id,\"a,b\"\r\n");
+
+        let values = vec![
+            ColumnValue::Varchar("plain".to_string()),
+            ColumnValue::Varchar("say \"hi\"".to_string()),
+            ColumnValue::Varchar("a,b".to_string()),
+            ColumnValue::Varchar("line\nbreak\r\n".to_string()),
+        ];
+        assert_eq!(write(ResultFormat::Csv, &names(&["a", "b", "c", "d"]), &values),
+                   "plain,\"say \"\"hi\"\"\",\"a,b\",\"line\nbreak\r\n\"\r\n");
+    }
+
+    #[test]
+    fn csv_null_and_hidden_columns() {
+        // NULL is empty field, values after names are hidden columns of ordering key
+        let values = vec![ColumnValue::Null, ColumnValue::Int32(1), ColumnValue::Null, ColumnValue::Int64(2)];
+        assert_eq!(write(ResultFormat::Csv, &names(&["a", "b", "c"]), &values), ",1,\r\n");
+    }
+
+    #[test]
+    fn json_exact_decimal() {
+        let values = vec![
+            ColumnValue::Decimal("1234567890123456789.012345678".parse().unwrap()),
+            ColumnValue::Decimal("-0.10".parse().unwrap()),
+        ];
+        assert_eq!(write(ResultFormat::Json, &names(&["a", "b"]), &values),
+                   "{\"a\":1234567890123456789.012345678,\"b\":-0.10}");
+    }
+
+    #[test]
+    fn json_value_formats() {
+        let values = vec![ColumnValue::Decimal("1.50".parse().unwrap()), ColumnValue::Raw(vec![0xfb, 0xff])];
+        let formats = ValueFormats { decimal: DecimalFormat::String, binary: BinaryFormat::Base64 };
+        let mut buf = Vec::new();
+        write_row(&mut buf, ResultFormat::Json, formats, &names(&["a", "b"]), &values);
+        assert_eq!(String::from_utf8(buf).unwrap(), "{\"a\":\"1.50\",\"b\":\"-_8\"}");
+        assert_eq!(write(ResultFormat::Csv, &names(&["a", "b"]), &values), "1.50,fbff\r\n");
+
+        // base64 is accepted with standard alphabet and padding
+        assert_eq!(BinaryFormat::Base64.parse_value("+/8=").unwrap(), vec![0xfb, 0xff]);
+        assert_eq!(BinaryFormat::Hex.parse_value("FBff").unwrap(), vec![0xfb, 0xff]);
+        assert!(BinaryFormat::Hex.parse_value("-_8").is_err());
+    }
+
+    #[test]
+    fn xml_escaping_and_null() {
+        let values = vec![
+            ColumnValue::Varchar("<a> & \"b\"".to_string()),
+            ColumnValue::Null,
+            ColumnValue::Int16(7),
+        ];
+        assert_eq!(write(ResultFormat::Xml, &names(&["a", "b", "c"]), &values),
+                   "<row><a>&lt;a&gt; &amp; &quot;b&quot;</a><b nil=\"true\"/><c>7</c></row>");
+    }
+
+    #[test]
+    fn xml_element_names() {
+        let values = vec![ColumnValue::Int16(1), ColumnValue::Int16(2), ColumnValue::Int16(3), ColumnValue::Null];
+        assert_eq!(write(ResultFormat::Xml, &names(&["sys$id", "no#", "1st col", "a_x"]), &values),
+                   "<row><sys_x0024_id>1</sys_x0024_id><no_x0023_>2</no_x0023_><_x0031_st_x0020_col>3</_x0031_st_x0020_col><a_x005F_x nil=\"true\"/></row>");
+    }
+
+    #[test]
+    fn xml_expanded_rows() {
+        let parent = ColumnValue::Object(Arc::new(names(&["id"])), vec![ColumnValue::Int64(1)]);
+        let children = ColumnValue::Array(Arc::new(names(&["id"])), vec![vec![ColumnValue::Int64(2)], vec![ColumnValue::Null]]);
+        assert_eq!(write(ResultFormat::Xml, &names(&["parent", "children"]), &[parent, children]),
+                   "<row><parent><id>1</id></parent><children><row><id>2</id></row><row><id nil=\"true\"/></row></children></row>");
+    }
+
+    #[test]
+    fn timestamp_round_trip() {
+        for text in ["2021-03-28T02:30:00", "2020-12-23T10:15:30.500", "1999-01-01T00:00:00.000000001"] {
+            let timestamp = parse_timestamp(text).unwrap();
+            assert_eq!(format_timestamp(&timestamp), text);
+        }
+        assert_eq!(format_timestamp(&parse_timestamp("2020-12-23T10:15:30.5").unwrap()), "2020-12-23T10:15:30.500");
+
+        let value = ColumnValue::Timestamp(parse_timestamp("2021-03-28T02:30:00").unwrap());
+        assert_eq!(write(ResultFormat::Json, &names(&["a"]), &[value]), "{\"a\":\"2021-03-28T02:30:00\"}");
+
+        // value with offset is in local time zone
+        assert_eq!(parse_timestamp("2020-12-23T10:15:30Z").unwrap(),
+                   DateTime::parse_from_rfc3339("2020-12-23T10:15:30Z").unwrap().with_timezone(&Local).naive_local());
+        assert!(parse_timestamp("2020-12-23").is_err());
+    }
+
+    #[test]
+    fn interval_ym_round_trip() {
+        for text in ["P1Y2M", "-P1Y2M", "P0Y0M", "P100Y11M"] {
+            let interval = parse_interval_ym(text).unwrap();
+            assert_eq!(format_interval_ym(&interval), text);
+        }
+
+        let interval = parse_interval_ym("P14M").unwrap();
+        assert_eq!((interval.years, interval.months), (0, 14));
+        assert!(parse_interval_ym("P1D").is_err());
+        assert!(parse_interval_ym("1Y").is_err());
+        assert!(parse_interval_ym("PT1H").is_err());
+    }
+
+    #[test]
+    fn interval_ds_round_trip() {
+        for text in ["P1DT2H3M4S", "-P1DT2H3M4.5S", "P0DT0H0M0S", "P0DT0H0M0.000000001S", "-P0DT0H0M0.25S"] {
+            let interval = parse_interval_ds(text).unwrap();
+            assert_eq!(format_interval_ds(&interval), text);
+        }
+
+        let interval = parse_interval_ds("PT36H").unwrap();
+        assert_eq!(interval, Duration::hours(36));
+        assert_eq!(format_interval_ds(&interval), "P1DT12H0M0S");
+        assert!(parse_interval_ds("P1Y").is_err());
+        assert!(parse_interval_ds("PT1.5M").is_err());
+        assert!(parse_interval_ds("PT1.0000000001S").is_err());
+        assert!(parse_interval_ds("P").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::metainfo as mi;
+use crate::application::query::{self, ColTypeInfo, ParsedParameter};
+use crate::application::filter::{Predicate, FilterParams};
+use crate::application::encoding::{self, ColumnValue};
+
+// `expand` parameter, comma-separated list of relations embedded in result rows:
+//   expand=departments                parent row by foreign key of table, object or null
+//   expand=job_history                child rows of table with foreign key to table, array
+//   expand=departments.locations      nested relation of expanded rows
+// relation is named by related table, or by name of foreign key if table has several relations with it.
+// each relation is fetched by one batched query with keys of all rows: WHERE fk IN (:1,:2,...)
+
+/// maximum depth of nested relations
+const MAX_EXPAND_DEPTH: usize = 2;
+/// maximum count of relations in one request, including nested
+const MAX_EXPANSIONS: usize = 5;
+/// maximum count of rows fetched for one relation, it is also limit of keys in IN list
+const MAX_EXPAND_ROWS: usize = 1000;
+
+/// Relation embedded in result rows: parent row or child rows
+pub struct Expansion {
+    pub name: String,
+    many:     bool,
+
+    // positions of key columns in selected columns of owner query
+    local_indices: Vec<usize>,
+
+    // related table, all columns except LOB columns are selected
+    table_name:     String,
+    columns:        Vec<ColTypeInfo>,
+    column_names:   Vec<String>,
+    remote_indices: Vec<usize>,
+
+    // names of columns and nested relations in result
+    result_len: usize,
+    names:      Arc<Vec<String>>,
+    nested:     Vec<Expansion>,
+}
+
+/// Foreign key between table and related table, from side of table
+struct Relation<'a> {
+    many:           bool,
+    schema_name:    &'a str,
+    table_info:     &'a mi::TableInfo,
+    local_columns:  Vec<usize>,
+    remote_columns: Vec<usize>,
+}
+
+/// parse `expand` parameter, check relations with metainfo.
+/// key columns absent in projection of query are selected as hidden columns
+pub fn parse_expand<'a>(metainfo:     &'a mi::MetaInfo,
+                        schema_name:  &str,
+                        table_info:   &'a mi::TableInfo,
+                        expand:       Vec<String>,
+                        columns:      &mut Vec<ColTypeInfo>,
+                        column_names: &mut Vec<String>
+) -> Result<Vec<Expansion>, String> {
+    let paths: Vec<Vec<&str>> = expand.iter().map(|path|path.trim().split('.').collect()).collect();
+    let mut count = 0;
+    build_expansions(metainfo, schema_name, table_info, &paths, 1, &mut count, columns, column_names)
+}
+
+/// fetch relations of rows, remove hidden columns and append relations after result columns
+pub fn expand_rows(conn: &oracle::Connection, expansions: &[Expansion], rows: &mut Vec<Vec<ColumnValue>>, result_len: usize) -> Result<(), String> {
+    if expansions.is_empty() {
+        return Ok(());
+    }
+
+    let mut expanded = expansions
+        .iter()
+        .map(|expansion|expansion.fetch(conn, rows).map(|values|values.into_iter()))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    for row in rows.iter_mut() {
+        row.truncate(result_len);
+        for values in expanded.iter_mut() {
+            row.push(values.next().unwrap_or(ColumnValue::Null));
+        }
+    }
+
+    Ok(())
+}
+
+fn build_expansions<'a>(metainfo:     &'a mi::MetaInfo,
+                        schema_name:  &str,
+                        table_info:   &'a mi::TableInfo,
+                        paths:        &[Vec<&str>],
+                        depth:        usize,
+                        count:        &mut usize,
+                        columns:      &mut Vec<ColTypeInfo>,
+                        column_names: &mut Vec<String>
+) -> Result<Vec<Expansion>, String> {
+    let mut names: Vec<&str> = Vec::new();
+    for path in paths {
+        if !names.contains(&path[0]) {
+            names.push(path[0]);
+        }
+    }
+
+    let mut expansions = Vec::with_capacity(names.len());
+
+    for name in names {
+        if name.is_empty() {
+            return Err("Empty name of relation in expand".to_string());
+        }
+        if depth > MAX_EXPAND_DEPTH {
+            return Err(format!("Depth of nested relations in expand must be <= {}", MAX_EXPAND_DEPTH));
+        }
+        *count += 1;
+        if *count > MAX_EXPANSIONS {
+            return Err(format!("Count of relations in expand must be <= {}", MAX_EXPANSIONS));
+        }
+
+        let relation = find_relation(metainfo, schema_name, table_info, name)?;
+
+        let local_indices = relation.local_columns
+            .iter()
+            .map(|idx|query::select_hidden(columns, column_names, &table_info.columns[*idx]))
+            .collect();
+
+        // LOB columns are not fetched, so positions of related columns differ from metainfo
+        let mut related_columns: Vec<ColTypeInfo> = relation.table_info.columns.iter().filter(|c|!c.is_lob()).map(ColTypeInfo::new).collect();
+        let mut related_column_names: Vec<String> = relation.table_info.columns.iter().filter(|c|!c.is_lob()).map(|c|c.name.to_owned()).collect();
+        let result_len = related_column_names.len();
+
+        let remote_indices = relation.remote_columns
+            .iter()
+            .map(|idx|query::select_hidden(&mut related_columns, &mut related_column_names, &relation.table_info.columns[*idx]))
+            .collect();
+
+        let nested_paths: Vec<Vec<&str>> = paths
+            .iter()
+            .filter(|path|path[0] == name && path.len() > 1)
+            .map(|path|path[1..].to_vec())
+            .collect();
+        let nested = build_expansions(metainfo, relation.schema_name, relation.table_info, &nested_paths, depth + 1, count,
+                                      &mut related_columns, &mut related_column_names)?;
+
+        let mut names: Vec<String> = related_column_names[..result_len].to_vec();
+        for expansion in &nested {
+            if names.contains(&expansion.name) {
+                return Err(format!("Name of relation '{}' is same as column of table {}", expansion.name, relation.table_info.name));
+            }
+            names.push(expansion.name.to_owned());
+        }
+
+        expansions.push( Expansion {
+            name: name.to_string(),
+            many: relation.many,
+            local_indices,
+            table_name: format!("{}.{}", relation.schema_name, relation.table_info.name),
+            columns: related_columns,
+            column_names: related_column_names,
+            remote_indices,
+            result_len,
+            names: Arc::new(names),
+            nested
+        });
+    }
+
+    Ok(expansions)
+}
+
+/// find parent table by foreign key of table or child table by its foreign key to table.
+/// name of foreign key is resolved to parent at first, it differs relations of self-referenced table
+fn find_relation<'a>(metainfo: &'a mi::MetaInfo, schema_name: &str, table_info: &'a mi::TableInfo, name: &str) -> Result<Relation<'a>, String> {
+    let mut found = Vec::new();
+
+    for fk in &table_info.foreign_keys {
+        let by_fk_name = fk.name.eq_ignore_ascii_case(name);
+        if by_fk_name || fk.ref_table == name {
+            let parent = metainfo.schemas
+                .get(fk.ref_schema.as_str())
+                .and_then(|schema|schema.tables.get(fk.ref_table.as_str()))
+                .ok_or_else(||format!("Table {}.{} referenced by {} is not available", fk.ref_schema, fk.ref_table, fk.name))?;
+
+            let remote_columns = column_positions(parent, &fk.ref_columns)?;
+            let relation = Relation {
+                many: false, schema_name: fk.ref_schema.as_str(), table_info: parent,
+                local_columns: fk.column_indices.clone(), remote_columns };
+
+            if by_fk_name {
+                return Ok(relation);
+            }
+            found.push(relation);
+        }
+    }
+
+    for schema in metainfo.schemas.iter() {
+        for child in schema.tables.iter() {
+            let references = child.foreign_keys
+                .iter()
+                .filter(|fk|fk.ref_schema == schema_name && fk.ref_table == table_info.name)
+                .filter(|fk|child.name == name || fk.name.eq_ignore_ascii_case(name));
+
+            for fk in references {
+                let local_columns = column_positions(table_info, &fk.ref_columns)?;
+                found.push( Relation {
+                    many: true, schema_name: schema.name.as_str(), table_info: child,
+                    local_columns, remote_columns: fk.column_indices.clone() } );
+            }
+        }
+    }
+
+    match found.len() {
+        0 => Err(format!("Relation '{}' not found for table {}", name, table_info.name)),
+        1 => Ok(found.remove(0)),
+        _ => Err(format!("Relation '{}' of table {} is ambiguous, use name of foreign key", name, table_info.name))
+    }
+}
+
+fn column_positions(table_info: &mi::TableInfo, names: &[String]) -> Result<Vec<usize>, String> {
+    names.iter()
+        .map(|name| {
+            table_info.columns
+                .iter()
+                .position(|c|&c.name == name)
+                .ok_or_else(||format!("Key column {} of table {} is not supported", name, table_info.name))
+        })
+        .collect()
+}
+
+impl Expansion {
+    /// fetch related rows for all rows by one query, result has one value for each row
+    fn fetch(&self, conn: &oracle::Connection, rows: &[Vec<ColumnValue>]) -> Result<Vec<ColumnValue>, String> {
+        // rows with NULL in key have no related rows
+        let row_keys: Vec<Option<Vec<String>>> = rows
+            .iter()
+            .map(|row|self.local_indices.iter().map(|idx|row[*idx].to_text(encoding::INTERNAL_BINARY)).collect())
+            .collect();
+
+        let mut unique = HashSet::new();
+        let keys: Vec<&Vec<String>> = row_keys.iter().flatten().filter(|key|unique.insert(*key)).collect();
+
+        let mut related: HashMap<Vec<String>, Vec<Vec<ColumnValue>>> = HashMap::new();
+
+        if !keys.is_empty() {
+            if keys.len() > MAX_EXPAND_ROWS {
+                return Err(format!("Relation '{}' can be expanded for {} rows at most", self.name, MAX_EXPAND_ROWS));
+            }
+
+            let mut params = FilterParams::new();
+            let condition = self.key_predicate(&keys)?.generate_sql(&mut params);
+            let sql = format!("SELECT {} FROM {} WHERE {} FETCH NEXT {} ROWS ONLY",
+                              self.column_names.join(","), self.table_name, condition, MAX_EXPAND_ROWS + 1);
+
+            let mut fetched = query::fetch_rows(conn, &sql, params, self.columns.clone())?;
+
+            if fetched.len() > MAX_EXPAND_ROWS {
+                return Err(format!("Relation '{}' has more than {} rows, query table {} with filter", self.name, MAX_EXPAND_ROWS, self.table_name));
+            }
+
+            // keys are taken before nested relations remove hidden columns
+            let fetched_keys: Vec<Vec<String>> = fetched
+                .iter()
+                .map(|row|self.remote_indices.iter().map(|idx|row[*idx].to_text(encoding::INTERNAL_BINARY).unwrap_or_default()).collect())
+                .collect();
+
+            expand_rows(conn, &self.nested, &mut fetched, self.result_len)?;
+
+            for (key, row) in fetched_keys.into_iter().zip(fetched) {
+                related.entry(key).or_insert_with(Vec::new).push(row);
+            }
+        }
+
+        let values = row_keys
+            .into_iter()
+            .map(|key| {
+                let rows = key.and_then(|key|related.get(&key));
+                if self.many {
+                    ColumnValue::Array(self.names.clone(), rows.cloned().unwrap_or_default())
+                } else {
+                    match rows.and_then(|rows|rows.first()) {
+                        Some(row) => ColumnValue::Object(self.names.clone(), row.clone()),
+                        None => ColumnValue::Null
+                    }
+                }
+            })
+            .collect();
+
+        Ok(values)
+    }
+
+    /// `fk IN (:1,:2)` for one key column, `(fk1 = :1 AND fk2 = :2) OR (...)` for composite key
+    fn key_predicate(&self, keys: &[&Vec<String>]) -> Result<Predicate, String> {
+        let key_names: Vec<String> = self.remote_indices.iter().map(|idx|self.column_names[*idx].to_owned()).collect();
+        let key_columns: Vec<ColTypeInfo> = self.remote_indices.iter().map(|idx|self.columns[*idx]).collect();
+
+        let parse = |col: &ColTypeInfo, value: &String| {
+            ParsedParameter::parse(col, value.to_owned(), encoding::INTERNAL_BINARY)
+                .map_err(|err|format!("Can not parse key of relation '{}': {}", self.name, err))
+        };
+
+        if key_columns.len() == 1 {
+            let values = keys
+                .iter()
+                .map(|key|parse(&key_columns[0], &key[0]))
+                .collect::<Result<Vec<ParsedParameter>, String>>()?;
+            return Ok(Predicate::In { column: key_names[0].to_owned(), col_type: key_columns[0], values });
+        }
+
+        let alternatives = keys
+            .iter()
+            .map(|key| {
+                let values = key_columns
+                    .iter()
+                    .zip(key.iter())
+                    .map(|(col, value)|parse(col, value))
+                    .collect::<Result<Vec<ParsedParameter>, String>>()?;
+                Ok(Predicate::all_equal(key_names.clone(), key_columns.clone(), values))
+            })
+            .collect::<Result<Vec<Predicate>, String>>()?;
+
+        Ok(Predicate::Or(alternatives))
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+use actix_web::{get, web, Responder, HttpResponse, HttpRequest};
+use actix_web::web::Bytes;
+use actix_web::dev::HttpServiceFactory;
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+
+use crate::application::{ApplicationState, query, request_context};
+use crate::application::api_scope::{parse_filter, split_list};
+
+/// count of chunks buffered between oracle session and http response
+const EXPORT_CHANNEL_SIZE: usize = 4;
+
+// group of endpoints for export of whole tables, requires dedicated permission
+pub fn export_scope() -> impl HttpServiceFactory {
+    web::scope("/export")
+        .wrap(crate::security::Authorized::exporters())
+        .service(table_export)
+}
+
+#[derive(Deserialize)]
+struct ExportParams {
+    q:      Option<String>,
+    select: Option<String>,
+    order:  Option<String>,
+    format: Option<String>,
+}
+
+/// stream all rows of table (with optional filter) as NDJSON, JSON array, CSV or XML, without limit of rows
+#[get("/schemas/{schema}/{table}/")]
+async fn table_export(path: web::Path<(String,String)>, req: web::Query<ExportParams>, http_req: HttpRequest, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let (schema_name,table_name) = path.into_inner();
+    let metainfo = data.metainfo.read().unwrap();
+
+    if let Some(info) = metainfo.schemas.get(schema_name.as_str()) {
+        if let Some(info) = info.tables.get(table_name.as_str()) {
+            let predicate = match parse_filter(info, &req.q, data.formats.binary) {
+                Ok(predicate) => predicate,
+                Err(err) => return HttpResponse::BadRequest().body(err)
+            };
+            let format = match query::ExportFormat::parse(req.format.as_deref().unwrap_or("ndjson")) {
+                Ok(format) => format,
+                Err(err) => return HttpResponse::BadRequest().body(err)
+            };
+            let select = split_list(&req.select);
+            let order = split_list(&req.order);
+
+            return match query::DynamicQuery::create_for_export(&schema_name, info, predicate, select, order) {
+                Ok(query) => {
+                    // streaming is not limited by execution time of route, it is timeout of each call
+                    let query = query.with_context(request_context(&http_req, &data));
+                    // bounded channel: export waits while client reads previous chunks.
+                    // error of channel is sent from blocking thread, so it is converted to actix error in response
+                    let (mut tx, rx) = mpsc::channel::<Result<Bytes, String>>(EXPORT_CHANNEL_SIZE);
+                    let mut chunks_tx = tx.clone();
+
+                    actix_web::rt::spawn(async move {
+                        let result = web::block(move || {
+                            query.export(format, |chunk| block_on(chunks_tx.send(Ok(Bytes::from(chunk)))).is_ok())
+                        }).await;
+
+                        if let Err(e) = result {
+                            // response is already started, error aborts it
+                            eprintln!("{:?}",e);
+                            let _ = tx.send(Err("Export failed".to_string())).await;
+                        }
+                    });
+
+                    HttpResponse::Ok()
+                        .content_type(format.content_type())
+                        .streaming(rx.map(|chunk|chunk.map_err(actix_web::error::ErrorInternalServerError)))
+                },
+                Err(err) => HttpResponse::BadRequest().body(err)
+            };
+        }
+    };
+
+    HttpResponse::NotFound().finish()
+}
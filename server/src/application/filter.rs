@@ -0,0 +1,329 @@
+use serde_json::{Map, Value};
+
+use crate::metainfo as mi;
+use crate::application::query::{ColTypeInfo, ParsedParameter};
+use crate::application::encoding::BinaryFormat;
+
+// filter grammar for `q` parameter, json object with column predicates joined by AND:
+//   {"name": "AB"}                          name = :1
+//   {"name": null}                          name IS NULL
+//   {"amount": {"gt": 100, "lte": 500}}     amount > :1 AND amount <= :2
+//   {"name": {"like": "AB%"}}               name LIKE :1
+//   {"id": {"in": [1,2,3]}}                 id IN (:1,:2,:3)
+//   {"name": {"null": false}}               name IS NOT NULL
+//   {"or": [{...}, {...}]}                  ( ... OR ... )
+//   {"and": [{...}, {...}]}                 ( ... AND ... )
+// all values are binded as parameters, never inlined in sql
+
+/// Oracle limit for count of expressions in IN list
+const MAX_IN_LIST_SIZE: usize = 1000;
+
+#[derive(Clone)]
+pub enum CompareOperator {
+    Eq, Ne, Gt, Gte, Lt, Lte, Like
+}
+
+/// Parsed and validated predicate tree of where clause
+#[derive(Clone)]
+pub enum Predicate {
+    Compare { column: String, op: CompareOperator, col_type: ColTypeInfo, value: ParsedParameter },
+    In      { column: String, col_type: ColTypeInfo, values: Vec<ParsedParameter> },
+    IsNull  { column: String, is_null: bool },
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+}
+
+/// Parameters of generated where clause in order of placeholders
+#[derive(Clone)]
+pub struct FilterParams {
+    pub columns: Vec<ColTypeInfo>,
+    pub values:  Vec<ParsedParameter>,
+}
+
+impl CompareOperator {
+    fn parse(name: &str) -> Option<CompareOperator> {
+        match name {
+            "eq"   => Some(CompareOperator::Eq),
+            "ne"   => Some(CompareOperator::Ne),
+            "gt"   => Some(CompareOperator::Gt),
+            "gte"  => Some(CompareOperator::Gte),
+            "lt"   => Some(CompareOperator::Lt),
+            "lte"  => Some(CompareOperator::Lte),
+            "like" => Some(CompareOperator::Like),
+            _ => None
+        }
+    }
+
+    fn sql(&self) -> &'static str {
+        match self {
+            CompareOperator::Eq   => "=",
+            CompareOperator::Ne   => "<>",
+            CompareOperator::Gt   => ">",
+            CompareOperator::Gte  => ">=",
+            CompareOperator::Lt   => "<",
+            CompareOperator::Lte  => "<=",
+            CompareOperator::Like => "LIKE",
+        }
+    }
+}
+
+impl Predicate {
+    /// parse `q` parameter, check columns, operators and values with metainfo.
+    /// binary values are in format of configuration
+    pub fn parse(table_info: &mi::TableInfo, value: &Value, binary: BinaryFormat) -> Result<Predicate, String> {
+        match value {
+            Value::Object(map) => parse_object(table_info, map, binary),
+            _ => Err("Filter must be a json object".to_string())
+        }
+    }
+
+    /// predicate with equality of all columns, used for primary keys
+    pub fn all_equal(column_names: Vec<String>, columns: Vec<ColTypeInfo>, values: Vec<ParsedParameter>) -> Predicate {
+        let predicates = column_names
+            .into_iter()
+            .zip(columns)
+            .zip(values)
+            .map(|((column, col_type), value)| Predicate::Compare { column, op: CompareOperator::Eq, col_type, value })
+            .collect();
+        Predicate::And(predicates)
+    }
+
+    /// predicate without conditions, where clause is not needed
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Predicate::And(predicates) => predicates.iter().all(|p|p.is_empty()),
+            _ => false
+        }
+    }
+
+    /// generate sql condition, move binded values to params in order of placeholders
+    pub fn generate_sql(self, params: &mut FilterParams) -> String {
+        match self {
+            Predicate::Compare { column, op, col_type, value } => {
+                params.push(col_type, value);
+                format!("{} {} :{}", column, op.sql(), params.len())
+            },
+            Predicate::In { column, col_type, values } => {
+                let placeholders: Vec<String> = values
+                    .into_iter()
+                    .map(|value| {
+                        params.push(col_type, value);
+                        format!(":{}", params.len())
+                    })
+                    .collect();
+                format!("{} IN ({})", column, placeholders.join(","))
+            },
+            Predicate::IsNull { column, is_null } => {
+                if is_null {
+                    format!("{} IS NULL", column)
+                } else {
+                    format!("{} IS NOT NULL", column)
+                }
+            },
+            Predicate::And(predicates) => join_predicates(predicates, " AND ", "1=1", params),
+            Predicate::Or(predicates) => join_predicates(predicates, " OR ", "1=0", params),
+        }
+    }
+}
+
+impl FilterParams {
+    pub fn new() -> FilterParams {
+        FilterParams { columns: Vec::new(), values: Vec::new() }
+    }
+
+    fn push(&mut self, col_type: ColTypeInfo, value: ParsedParameter) {
+        self.columns.push(col_type);
+        self.values.push(value);
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+}
+
+fn join_predicates(predicates: Vec<Predicate>, separator: &str, empty: &str, params: &mut FilterParams) -> String {
+    match predicates.len() {
+        0 => empty.to_string(),
+        1 => predicates.into_iter().next().unwrap().generate_sql(params),
+        _ => {
+            let conditions: Vec<String> = predicates.into_iter().map(|p|p.generate_sql(params)).collect();
+            format!("({})", conditions.join(separator))
+        }
+    }
+}
+
+fn parse_object(table_info: &mi::TableInfo, map: &Map<String,Value>, binary: BinaryFormat) -> Result<Predicate, String> {
+    let mut predicates = Vec::with_capacity(map.len());
+
+    for (key, value) in map {
+        let predicate = match key.as_str() {
+            "or"  => Predicate::Or(parse_group(table_info, key, value, binary)?),
+            "and" => Predicate::And(parse_group(table_info, key, value, binary)?),
+            _     => parse_column(table_info, key, value, binary)?
+        };
+        predicates.push(predicate);
+    }
+
+    Ok(Predicate::And(predicates))
+}
+
+fn parse_group(table_info: &mi::TableInfo, key: &str, value: &Value, binary: BinaryFormat) -> Result<Vec<Predicate>, String> {
+    match value {
+        Value::Array(items) => items.iter().map(|item|Predicate::parse(table_info, item, binary)).collect(),
+        _ => Err(format!("Value of '{}' group must be an array of json objects", key))
+    }
+}
+
+fn parse_column(table_info: &mi::TableInfo, col_name: &str, value: &Value, binary: BinaryFormat) -> Result<Predicate, String> {
+    let column = table_info.columns
+        .iter()
+        .find(|c|c.name == col_name)
+        .ok_or_else(||format!("Not found column {}", col_name))?;
+
+    match value {
+        Value::Object(operators) => {
+            let predicates = operators
+                .iter()
+                .map(|(op, operand)|parse_operator(column, op, operand, binary))
+                .collect::<Result<Vec<Predicate>, String>>()?;
+            Ok(Predicate::And(predicates))
+        },
+        Value::Null => Ok(Predicate::IsNull { column: column.name.to_owned(), is_null: true }),
+        _ => parse_operator(column, "eq", value, binary)
+    }
+}
+
+fn parse_operator(column: &mi::ColumnInfo, op: &str, operand: &Value, binary: BinaryFormat) -> Result<Predicate, String> {
+    let col_name = column.name.to_owned();
+
+    match op {
+        "null" => {
+            match operand {
+                Value::Bool(is_null) => Ok(Predicate::IsNull { column: col_name, is_null: *is_null }),
+                _ => Err(format!("Operand of 'null' for column {} must be boolean", col_name))
+            }
+        },
+        "in" => {
+            let items = match operand {
+                Value::Array(items) => items,
+                _ => return Err(format!("Operand of 'in' for column {} must be an array", col_name))
+            };
+            if items.is_empty() || items.len() > MAX_IN_LIST_SIZE {
+                return Err(format!("Operand of 'in' for column {} must have from 1 to {} values", col_name, MAX_IN_LIST_SIZE));
+            }
+            let values = items
+                .iter()
+                .map(|item|parse_value(column, item, binary))
+                .collect::<Result<Vec<ParsedParameter>, String>>()?;
+            Ok(Predicate::In { column: col_name, col_type: ColTypeInfo::new(column), values })
+        },
+        _ => {
+            let op = CompareOperator::parse(op)
+                .ok_or_else(||format!("Unknown operator '{}' for column {}", op, col_name))?;
+
+            if let CompareOperator::Like = op {
+                if column.col_type != oracle::SqlType::Varchar {
+                    return Err(format!("Operator 'like' is supported only for string column, but {} is not", col_name));
+                }
+            }
+
+            let value = parse_value(column, operand, binary)?;
+            Ok(Predicate::Compare { column: col_name, op, col_type: ColTypeInfo::new(column), value })
+        }
+    }
+}
+
+fn parse_value(column: &mi::ColumnInfo, value: &Value, binary: BinaryFormat) -> Result<ParsedParameter, String> {
+    let parsed = ParsedParameter::parse_json(&ColTypeInfo::new(column), value, binary)
+        .map_err(|err|format!("Can not parse parameter value {} for column {}: {}", value, column.name, err))?;
+
+    if parsed.is_null() {
+        Err(format!("NULL value for column {} is not comparable, use {{\"null\": true}} operator", column.name))
+    } else {
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use oracle::SqlType;
+
+    fn table_info() -> mi::TableInfo {
+        mi::TableInfo::for_test("items", &[
+            ("id", SqlType::Int64, false),
+            ("name", SqlType::Varchar, true),
+            ("amount", SqlType::Float64, true),
+        ])
+    }
+
+    /// generated condition and count of binded parameters
+    fn generate(filter: Value) -> Result<(String, usize), String> {
+        let predicate = Predicate::parse(&table_info(), &filter, BinaryFormat::Hex)?;
+        let mut params = FilterParams::new();
+        let sql = predicate.generate_sql(&mut params);
+        assert_eq!(params.columns.len(), params.len());
+        Ok((sql, params.len()))
+    }
+
+    #[test]
+    fn compare_operators() {
+        assert_eq!(generate(json!({"name": "AB"})).unwrap(), ("name = :1".to_string(), 1));
+        assert_eq!(generate(json!({"amount": {"gt": 100, "lte": 500}})).unwrap(),
+                   ("(amount > :1 AND amount <= :2)".to_string(), 2));
+        assert_eq!(generate(json!({"name": {"like": "AB%"}})).unwrap(), ("name LIKE :1".to_string(), 1));
+        assert_eq!(generate(json!({"id": {"ne": 1}})).unwrap(), ("id <> :1".to_string(), 1));
+    }
+
+    #[test]
+    fn null_predicates() {
+        assert_eq!(generate(json!({"name": null, "id": {"null": false}})).unwrap(),
+                   ("(id IS NOT NULL AND name IS NULL)".to_string(), 0));
+        assert!(generate(json!({"id": {"null": 1}})).is_err());
+        assert!(generate(json!({"id": {"eq": null}})).is_err());
+    }
+
+    #[test]
+    fn nested_or_and() {
+        let filter = json!({"or": [{"id": 1}, {"and": [{"name": {"like": "A%"}}, {"amount": {"gt": 100, "lte": 500}}]}]});
+        assert_eq!(generate(filter).unwrap(),
+                   ("(id = :1 OR (name LIKE :2 AND (amount > :3 AND amount <= :4)))".to_string(), 4));
+
+        assert_eq!(generate(json!({"or": []})).unwrap(), ("1=0".to_string(), 0));
+        assert!(generate(json!({"or": {"id": 1}})).is_err());
+    }
+
+    #[test]
+    fn empty_filter() {
+        let predicate = Predicate::parse(&table_info(), &json!({}), BinaryFormat::Hex).unwrap();
+        assert!(predicate.is_empty());
+        assert!(!Predicate::parse(&table_info(), &json!({"id": 1}), BinaryFormat::Hex).unwrap().is_empty());
+    }
+
+    #[test]
+    fn in_list_limit() {
+        let (sql, len) = generate(json!({"id": {"in": [1, 2, 3]}})).unwrap();
+        assert_eq!(sql, "id IN (:1,:2,:3)");
+        assert_eq!(len, 3);
+
+        let max: Vec<usize> = (0..MAX_IN_LIST_SIZE).collect();
+        assert_eq!(generate(json!({"id": {"in": max}})).unwrap().1, MAX_IN_LIST_SIZE);
+
+        let over: Vec<usize> = (0..=MAX_IN_LIST_SIZE).collect();
+        assert!(generate(json!({"id": {"in": over}})).is_err());
+        assert!(generate(json!({"id": {"in": []}})).is_err());
+        assert!(generate(json!({"id": {"in": 1}})).is_err());
+    }
+
+    #[test]
+    fn invalid_filters() {
+        assert!(generate(json!([1])).is_err());
+        assert!(generate(json!({"unknown": 1})).is_err());
+        assert!(generate(json!({"id": {"between": 1}})).is_err());
+        assert!(generate(json!({"id": {"like": "1%"}})).is_err());
+        assert!(generate(json!({"id": "abc"})).is_err());
+        // string longer than column
+        assert!(generate(json!({"name": "X".repeat(129)})).is_err());
+    }
+}
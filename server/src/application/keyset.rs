@@ -0,0 +1,140 @@
+use crate::metainfo as mi;
+use crate::utils::{hex_encode, hex_decode};
+use crate::application::query::{ColTypeInfo, ParsedParameter};
+use crate::application::filter::{Predicate, CompareOperator};
+use crate::application::encoding;
+
+/// Ordering key of table for keyset (seek) pagination:
+/// primary key or unique index with not nullable columns
+pub struct Keyset {
+    pub column_indices: Vec<usize>,
+    pub column_names:   Vec<String>,
+    columns:            Vec<ColTypeInfo>,
+}
+
+impl Keyset {
+    pub fn for_table(table_info: &mi::TableInfo) -> Result<Keyset, String> {
+        let column_indices = table_info.ordering_key()
+            .ok_or_else(||format!("Keyset pagination requires primary key or unique index in table {}", table_info.name))?;
+
+        let column_names = column_indices.iter().map(|idx|table_info.columns[*idx].name.to_owned()).collect();
+        let columns = column_indices.iter().map(|idx|ColTypeInfo::new(&table_info.columns[*idx])).collect();
+
+        Ok( Keyset { column_indices, column_names, columns } )
+    }
+
+    /// decode cursor into seek predicate `(k1,k2) > (:1,:2)`.
+    /// Oracle does not support comparison of row values, so it is expanded to
+    /// `k1 > :1 OR (k1 = :2 AND k2 > :3)`
+    pub fn seek_predicate(&self, cursor: &str) -> Result<Predicate, String> {
+        let values = self.decode_cursor(cursor)?;
+
+        let alternatives = (0..values.len())
+            .map(|i| {
+                let mut conditions: Vec<Predicate> = (0..i)
+                    .map(|j|self.compare(j, CompareOperator::Eq, &values))
+                    .collect();
+                conditions.push(self.compare(i, CompareOperator::Gt, &values));
+                Predicate::And(conditions)
+            })
+            .collect();
+
+        Ok(Predicate::Or(alternatives))
+    }
+
+    fn compare(&self, idx: usize, op: CompareOperator, values: &[ParsedParameter]) -> Predicate {
+        Predicate::Compare {
+            column:   self.column_names[idx].to_owned(),
+            op,
+            col_type: self.columns[idx],
+            value:    values[idx].clone()
+        }
+    }
+
+    fn decode_cursor(&self, cursor: &str) -> Result<Vec<ParsedParameter>, String> {
+        let bytes = hex_decode(cursor).map_err(|err|format!("Invalid cursor: {}", err))?;
+        let values: Vec<String> = serde_json::from_slice(&bytes).map_err(|err|format!("Invalid cursor: {}", err))?;
+
+        if values.len() != self.columns.len() {
+            return Err("Invalid cursor: count of values does not match with ordering key".to_string());
+        }
+
+        values.into_iter()
+            .zip(self.columns.iter())
+            .map(|(value, c)|ParsedParameter::parse(c, value, encoding::INTERNAL_BINARY).map_err(|err|format!("Invalid cursor: {}", err)))
+            .collect()
+    }
+}
+
+/// encode values of ordering key of last fetched row as opaque cursor
+pub fn encode_cursor(values: &[String]) -> String {
+    let json = serde_json::to_string(values).unwrap_or_default();
+    hex_encode(json.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::filter::FilterParams;
+    use oracle::SqlType;
+
+    fn table_info(primary_key: Option<Vec<usize>>) -> mi::TableInfo {
+        let mut table_info = mi::TableInfo::for_test("items", &[
+            ("id", SqlType::Int64, false),
+            ("name", SqlType::Varchar, false),
+            ("amount", SqlType::Float64, true),
+        ]);
+        table_info.primary_key = primary_key.map(|column_indices|mi::PrimaryKey { name: "pk_items".to_string(), column_indices });
+        table_info.indexes = vec![
+            mi::TableIndex { name: "ix_amount".to_string(), unique: true, columns: vec![mi::IndexColumn { column_index: 2, desc: false }] },
+            mi::TableIndex { name: "ix_name".to_string(), unique: true, columns: vec![mi::IndexColumn { column_index: 1, desc: false }] },
+        ];
+        table_info
+    }
+
+    #[test]
+    fn ordering_key() {
+        let keyset = Keyset::for_table(&table_info(Some(vec![0, 1]))).unwrap();
+        assert_eq!(keyset.column_names, vec!["id", "name"]);
+
+        // unique index with nullable column is skipped
+        let keyset = Keyset::for_table(&table_info(None)).unwrap();
+        assert_eq!(keyset.column_indices, vec![1]);
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let keyset = Keyset::for_table(&table_info(Some(vec![0, 1]))).unwrap();
+        let cursor = encode_cursor(&["42".to_string(), "A\"B".to_string()]);
+
+        let values = keyset.decode_cursor(&cursor).unwrap();
+        assert!(matches!(values[0], ParsedParameter::Int64(42)));
+        assert!(matches!(&values[1], ParsedParameter::Varchar(name) if name == "A\"B"));
+    }
+
+    #[test]
+    fn invalid_cursor() {
+        let keyset = Keyset::for_table(&table_info(Some(vec![0, 1]))).unwrap();
+        assert!(keyset.decode_cursor("not hex").is_err());
+        assert!(keyset.decode_cursor(&hex_encode(b"{}")).is_err());
+        assert!(keyset.decode_cursor(&encode_cursor(&["42".to_string()])).is_err());
+        assert!(keyset.decode_cursor(&encode_cursor(&["AB".to_string(), "42".to_string()])).is_err());
+    }
+
+    #[test]
+    fn seek_predicate_expansion() {
+        let keyset = Keyset::for_table(&table_info(Some(vec![0]))).unwrap();
+        let mut params = FilterParams::new();
+        let sql = keyset.seek_predicate(&encode_cursor(&["42".to_string()])).unwrap().generate_sql(&mut params);
+        assert_eq!(sql, "id > :1");
+        assert_eq!(params.values.len(), 1);
+
+        let keyset = Keyset::for_table(&table_info(Some(vec![0, 1, 2]))).unwrap();
+        let cursor = encode_cursor(&["42".to_string(), "AB".to_string(), "1.5".to_string()]);
+        let mut params = FilterParams::new();
+        let sql = keyset.seek_predicate(&cursor).unwrap().generate_sql(&mut params);
+        assert_eq!(sql, "(id > :1 OR (id = :2 AND name > :3) OR (id = :4 AND name = :5 AND amount > :6))");
+        assert_eq!(params.values.len(), 6);
+        assert!(matches!(params.values[4], ParsedParameter::Varchar(ref name) if name == "AB"));
+    }
+}
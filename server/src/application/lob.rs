@@ -0,0 +1,265 @@
+use oracle::{self, SqlLob, SqlBFile};
+use crate::{metainfo as mi, datasource};
+use crate::datasource::RequestContext;
+use crate::application::query::{ColTypeInfo, DynamicParamsProvider, ParsedParameter, parse_pk_params};
+use crate::application::modification::enumerate_pk_columns;
+use crate::application::encoding::BinaryFormat;
+
+/// size of chunk read from LOB by one round trip and sent to client
+const LOB_CHUNK_SIZE: usize = 64 * 1024;
+
+/// LOB column of one row by primary key, its content is streamed without limit of size
+pub struct LobColumn {
+    table_name:    String,
+    column_name:   String,
+    column:        ColTypeInfo,
+    pk_column_names: Vec<String>,
+    pk_columns:    Vec<ColTypeInfo>,
+    pk_params:     Vec<ParsedParameter>,
+    context:       RequestContext,
+}
+
+/// Response head of downloaded LOB, it is known after first chunk is read
+pub struct LobHead {
+    pub content_type: &'static str,
+    pub extension:    &'static str,
+    // length in bytes, it is known for binary LOB only
+    pub length:       Option<u64>,
+}
+
+struct LobResultsProvider {
+    column: ColTypeInfo
+}
+
+impl LobColumn {
+    pub fn create(schema_name: &str, table_info: &mi::TableInfo, pk_params: Vec<String>, column_name: &str, binary: BinaryFormat) -> Result<LobColumn, String> {
+        let column = table_info.columns
+            .iter()
+            .find(|c|c.name == column_name)
+            .ok_or_else(||format!("Column {} not found in table {}", column_name, table_info.name))?;
+
+        if !column.is_lob() {
+            return Err(format!("Column {} is not CLOB, NCLOB, BLOB or BFILE", column_name));
+        }
+
+        let (pk_column_names, pk_columns, pk_params) = parse_pk_params(table_info, pk_params, binary)?;
+
+        Ok( LobColumn {
+            table_name: format!("{}.{}", schema_name, table_info.name),
+            column_name: column.name.to_owned(),
+            column: ColTypeInfo::new(column),
+            pk_column_names, pk_columns, pk_params,
+            context: RequestContext::default() } )
+    }
+
+    /// tracing attributes, timeout and cancellation of oracle session
+    pub fn with_context(mut self, context: RequestContext) -> LobColumn {
+        self.context = context;
+        self
+    }
+
+    /// read LOB by chunks and send them to sink, head is called before first chunk.
+    /// head is None if row is not found or LOB is NULL. sink returns false if client is disconnected
+    pub fn download<H, F>(self, head: H, mut sink: F) -> Result<(),String>
+        where H: FnOnce(Option<LobHead>), F: FnMut(Vec<u8>) -> bool {
+        let conn = datasource::get_request_connection(&self.context)
+            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+
+        let lob = match self.select_locator(&conn, false)? {
+            Some(lob) => lob,
+            None => {
+                head(None);
+                return Ok(());
+            }
+        };
+
+        let character = lob.is_character()
+            .map_err(|err|format!("Can not get character set of LOB: {}", err))?;
+        let length = if character {
+            None
+        } else {
+            Some(conn.lob_length(&lob).map_err(|err|format!("Can not get length of LOB: {}", err))?)
+        };
+
+        let mut reader = conn.lob_reader(&lob)
+            .map_err(|err|format!("Can not open LOB: {}", err))?;
+
+        let mut chunk = next_chunk(&mut reader)?;
+        let (content_type, extension) = detect_content_type(&chunk, character);
+        head(Some( LobHead { content_type, extension, length } ));
+
+        while !chunk.is_empty() {
+            if !sink(chunk) {
+                return Ok(());
+            }
+            chunk = next_chunk(&mut reader)?;
+        }
+
+        Ok(())
+    }
+
+    /// replace content of LOB by chunks of request body and commit transaction, it is rolled back on error.
+    /// returns length of LOB (in characters for CLOB), None if row is not found
+    pub fn upload<I, B>(self, chunks: I) -> Result<Option<u64>,String>
+        where I: Iterator<Item = Result<B,String>>, B: AsRef<[u8]> {
+        let empty = match self.column.col_type {
+            oracle::SqlType::Blob => "EMPTY_BLOB()",
+            oracle::SqlType::Clob | oracle::SqlType::NClob => "EMPTY_CLOB()",
+            _ => return Err(format!("Column {} is BFILE, it is read only", self.column_name))
+        };
+
+        let conn = datasource::get_request_connection(&self.context)
+            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+        let tx = conn.begin();
+
+        // NULL is replaced by empty LOB, so locator can be selected for update
+        let sql = format!("UPDATE {} SET {} = {} WHERE {}",
+                          self.table_name, self.column_name, empty, enumerate_pk_columns(&self.pk_column_names, 0));
+        let row_count = tx.prepare_dynamic(&sql, Box::new( DynamicParamsProvider { columns: self.pk_columns.clone() }))
+            .and_then(|stmt|stmt.execute(self.pk_params.clone()))
+            .map_err(|err|format!("Can not execute statement: {}", err))?;
+        if row_count == 0 {
+            return Ok(None);
+        }
+
+        let lob = self.select_locator(&tx, true)?
+            .ok_or_else(||format!("LOB column {} is not found after update", self.column_name))?;
+
+        let length = {
+            let mut writer = tx.lob_writer(&lob)
+                .map_err(|err|format!("Can not open LOB for writing: {}", err))?;
+            for chunk in chunks {
+                writer.write_chunk(chunk?.as_ref())
+                    .map_err(|err|format!("Can not write LOB: {}", err))?;
+            }
+            writer.finish()
+                .map_err(|err|format!("Can not write LOB: {}", err))?
+        };
+
+        tx.commit()
+            .map_err(|err|format!("Can not commit transaction: {}", err))?;
+        Ok(Some(length))
+    }
+
+    /// SELECT col FROM table WHERE pk1 = :1, None if row is not found or LOB is NULL
+    fn select_locator(&self, conn: &oracle::Connection, for_update: bool) -> Result<Option<SqlLob>,String> {
+        let mut sql = format!("SELECT {} FROM {} WHERE {}",
+                              self.column_name, self.table_name, enumerate_pk_columns(&self.pk_column_names, 0));
+        if for_update {
+            sql.push_str(" FOR UPDATE");
+        }
+
+        let params_provider = Box::new( DynamicParamsProvider { columns: self.pk_columns.clone() });
+        let results_provider = Box::new( LobResultsProvider { column: self.column });
+
+        let query = conn.prepare_dynamic(&sql, params_provider)
+            .and_then(|stmt|stmt.query_dynamic(results_provider, 1))
+            .map_err(|err|format!("Can not prepare query: {}", err))?;
+
+        let lob = query.fetch_one(self.pk_params.clone())
+            .map_err(|err|format!("Can not fetch LOB: {}", err))?;
+        Ok(lob.flatten())
+    }
+}
+
+impl oracle::ResultsProvider<Option<SqlLob>> for LobResultsProvider {
+    fn sql_descriptors(&self) -> Vec<oracle::TypeDescriptor> {
+        vec![self.column.oci_data_type]
+    }
+
+    fn gen_result(&self, rs: oracle::ResultSet) -> Option<SqlLob> {
+        match self.column.col_type {
+            oracle::SqlType::BFile => Option::<SqlBFile>::from(rs[0]).map(SqlLob::from),
+            _ => rs[0].into()
+        }
+    }
+}
+
+/// read next chunk, LOB is read by chunks of fixed size until end
+fn next_chunk(reader: &mut oracle::LobReader) -> Result<Vec<u8>,String> {
+    let mut chunk = vec![0; LOB_CHUNK_SIZE];
+    let len = reader.read_chunk(&mut chunk)
+        .map_err(|err|format!("Can not read LOB: {}", err))?;
+    chunk.truncate(len);
+    Ok(chunk)
+}
+
+/// content type and file extension by first bytes of content.
+/// binary LOB is detected by signature of file format, character LOB by its first character
+fn detect_content_type(first: &[u8], character: bool) -> (&'static str, &'static str) {
+    if character {
+        let text = String::from_utf8_lossy(&first[..first.len().min(256)]);
+        let text = text.trim_start().to_ascii_lowercase();
+        return if text.starts_with("<!doctype html") || text.starts_with("<html") {
+            ("text/html; charset=utf-8", "html")
+        } else if text.starts_with('<') {
+            ("application/xml; charset=utf-8", "xml")
+        } else if text.starts_with('{') || text.starts_with('[') {
+            ("application/json; charset=utf-8", "json")
+        } else {
+            ("text/plain; charset=utf-8", "txt")
+        };
+    }
+
+    const SIGNATURES: [(&[u8], &str, &str); 8] = [
+        (b"%PDF-", "application/pdf", "pdf"),
+        (b"\x89PNG\r\n\x1a\n", "image/png", "png"),
+        (b"\xff\xd8\xff", "image/jpeg", "jpg"),
+        (b"GIF8", "image/gif", "gif"),
+        (b"PK\x03\x04", "application/zip", "zip"),
+        (b"\x1f\x8b", "application/gzip", "gz"),
+        (b"BM", "image/bmp", "bmp"),
+        (b"<?xml", "application/xml", "xml"),
+    ];
+
+    if first.len() >= 12 && &first[..4] == b"RIFF" && &first[8..12] == b"WEBP" {
+        return ("image/webp", "webp");
+    }
+    if let Some((_, content_type, extension)) = SIGNATURES.iter().find(|(signature, _, _)|first.starts_with(signature)) {
+        return (content_type, extension);
+    }
+
+    // text without signature, chunk may end in the middle of character
+    let text = match std::str::from_utf8(first) {
+        Ok(_) => !first.is_empty(),
+        Err(err) => err.error_len().is_none()
+    };
+    if text {
+        ("text/plain", "txt")
+    } else {
+        ("application/octet-stream", "bin")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_signatures() {
+        assert_eq!(detect_content_type(b"%PDF-1.7\n", false), ("application/pdf", "pdf"));
+        assert_eq!(detect_content_type(b"\x89PNG\r\n\x1a\n\0\0", false), ("image/png", "png"));
+        assert_eq!(detect_content_type(b"\xff\xd8\xff\xe0", false), ("image/jpeg", "jpg"));
+        assert_eq!(detect_content_type(b"RIFF\0\0\0\0WEBPVP8 ", false), ("image/webp", "webp"));
+        assert_eq!(detect_content_type(b"RIFF\xff\xff\0\0WAVE", false), ("application/octet-stream", "bin"));
+        assert_eq!(detect_content_type(b"<?xml version=\"1.0\"?>", false), ("application/xml", "xml"));
+    }
+
+    #[test]
+    fn binary_text() {
+        assert_eq!(detect_content_type("plain text".as_bytes(), false), ("text/plain", "txt"));
+        // chunk ends in the middle of 2-byte character
+        assert_eq!(detect_content_type(&"text é".as_bytes()[..6], false), ("text/plain", "txt"));
+        assert_eq!(detect_content_type(b"\x00\x01\xfe", false), ("application/octet-stream", "bin"));
+        assert_eq!(detect_content_type(b"", false), ("application/octet-stream", "bin"));
+    }
+
+    #[test]
+    fn character_content() {
+        assert_eq!(detect_content_type(b"  <!DOCTYPE html><html>", true), ("text/html; charset=utf-8", "html"));
+        assert_eq!(detect_content_type(b"<root/>", true), ("application/xml; charset=utf-8", "xml"));
+        assert_eq!(detect_content_type(b"\n[1, 2]", true), ("application/json; charset=utf-8", "json"));
+        assert_eq!(detect_content_type(b"hello", true), ("text/plain; charset=utf-8", "txt"));
+        assert_eq!(detect_content_type(b"", true), ("text/plain; charset=utf-8", "txt"));
+    }
+}
@@ -3,6 +3,7 @@ use actix_web::{get, web, Scope, Responder, HttpResponse};
 use serde::Serialize;
 
 use crate::application::ApplicationState;
+use crate::datasource;
 use crate::application;
 use actix_web::dev::HttpServiceFactory;
 use std::collections::HashSet;
@@ -12,8 +13,10 @@ use std::iter::FromIterator;
 pub fn management_scope() -> impl HttpServiceFactory {
     web::scope("/mgmt")
         .wrap(crate::security::Authorized::developers())
+        .service(datasource_stats)
         .service(schemas_metainfo)
         .service(tables_metainfo)
+        .service(procedures_metainfo)
         .service(table_metainfo)
 }
 
@@ -32,16 +35,20 @@ struct TableMetaInfoBrief<'a> {
     name:      &'a str,
     is_view:   bool,
     temporary: bool,
-    has_pk:    bool
+    has_pk:    bool,
+    comment:   Option<&'a str>
 }
 
 #[derive(Serialize)]
 struct TableMetaInfo<'a> {
-    name:      &'a str,
-    is_view:   bool,
-    temporary: bool,
-    has_pk:    bool,
-    columns:   Vec<ColumnMetaInfo<'a>>
+    name:          &'a str,
+    is_view:       bool,
+    temporary:     bool,
+    has_pk:        bool,
+    comment:       Option<&'a str>,
+    columns:       Vec<ColumnMetaInfo<'a>>,
+    foreign_keys:  Vec<ForeignKeyMetaInfo<'a>>,
+    referenced_by: Vec<ReferenceMetaInfo<'a>>
 }
 
 #[derive(Serialize)]
@@ -49,7 +56,87 @@ pub struct ColumnMetaInfo<'a> {
     pub name:     &'a str,
     pub col_type: &'static str,
     pub is_pk:    bool,
-    pub nullable: bool
+    pub nullable: bool,
+    pub comment:  Option<&'a str>
+}
+
+#[derive(Serialize)]
+struct ForeignKeyMetaInfo<'a> {
+    name:        &'a str,
+    columns:     Vec<&'a str>,
+    ref_schema:  &'a str,
+    ref_table:   &'a str,
+    ref_columns: Vec<&'a str>,
+    delete_rule: &'static str
+}
+
+/// foreign key of other table, which references this table (detail of master/detail)
+#[derive(Serialize)]
+struct ReferenceMetaInfo<'a> {
+    name:    &'a str,
+    schema:  &'a str,
+    table:   &'a str,
+    columns: Vec<&'a str>
+}
+
+#[derive(Serialize)]
+struct ProceduresMetainfo<'a> {
+    procedures: Vec<ProcedureMetaInfo<'a>>
+}
+
+/// procedure or function of schema or package, function has type of result
+#[derive(Serialize)]
+struct ProcedureMetaInfo<'a> {
+    package:     Option<&'a str>,
+    name:        &'a str,
+    overload:    Option<&'a str>,
+    unsupported: bool,
+    result:      Option<&'static str>,
+    arguments:   Vec<ArgumentMetaInfo<'a>>
+}
+
+#[derive(Serialize)]
+struct ArgumentMetaInfo<'a> {
+    name:      &'a str,
+    col_type:  &'static str,
+    direction: &'static str,
+    defaulted: bool
+}
+
+#[derive(Serialize)]
+struct DatasourceStats {
+    open_sessions:  u32,
+    busy_sessions:  u32,
+    min_sessions:   u32,
+    max_sessions:   u32,
+    increment:      u32,
+    connects:       u64,
+    connect_errors: u64,
+    connect_waits:  u64,
+    avg_connect_us: u64,
+    max_connect_us: u64,
+}
+
+#[get("/datasource")]
+async fn datasource_stats() -> impl Responder {
+    match datasource::stats() {
+        Ok(stats) => HttpResponse::Ok().json(DatasourceStats {
+            open_sessions: stats.pool.open,
+            busy_sessions: stats.pool.busy,
+            min_sessions: stats.pool.min,
+            max_sessions: stats.pool.max,
+            increment: stats.pool.increment,
+            connects: stats.connects,
+            connect_errors: stats.errors,
+            connect_waits: stats.waits,
+            avg_connect_us: stats.avg_latency_us,
+            max_connect_us: stats.max_latency_us
+        }),
+        Err(err) => {
+            eprintln!("{}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
 }
 
 #[get("/schemas")]
@@ -73,7 +160,8 @@ async fn tables_metainfo(path: web::Path<(String,)>, data: web::Data<Arc<Applica
                     name: info.name.as_str(),
                     is_view: info.is_view,
                     temporary: info.temporary,
-                    has_pk: info.primary_key.is_some()
+                    has_pk: info.primary_key.is_some(),
+                    comment: info.comment.as_deref()
                 }).collect();
             tables.sort_by(|a,b|a.name.cmp(b.name));
 
@@ -104,15 +192,47 @@ async fn table_metainfo(path: web::Path<(String,String)>, data: web::Data<Arc<Ap
                 .enumerate()
                 .map(|(ref i, c)| {
                     let is_pk = pk_indices.contains(i);
-                    ColumnMetaInfo { name: c.name.as_str(), col_type: c.col_type_name, is_pk, nullable: c.nullable}
+                    ColumnMetaInfo { name: c.name.as_str(), col_type: c.col_type_name, is_pk, nullable: c.nullable, comment: c.comment.as_deref() }
                 }).collect();
 
+            let foreign_keys = info
+                .foreign_keys
+                .iter()
+                .map(|fk| ForeignKeyMetaInfo {
+                    name: fk.name.as_str(),
+                    columns: fk.column_indices.iter().map(|i|info.columns[*i].name.as_str()).collect(),
+                    ref_schema: fk.ref_schema.as_str(),
+                    ref_table: fk.ref_table.as_str(),
+                    ref_columns: fk.ref_columns.iter().map(|c|c.as_str()).collect(),
+                    delete_rule: fk.delete_rule.as_str()
+                }).collect();
+
+            let mut referenced_by = Vec::new();
+            for schema in metainfo.schemas.iter() {
+                for table in schema.tables.iter() {
+                    let references = table.foreign_keys
+                        .iter()
+                        .filter(|fk|fk.ref_schema == schema_name && fk.ref_table == info.name)
+                        .map(|fk| ReferenceMetaInfo {
+                            name: fk.name.as_str(),
+                            schema: schema.name.as_str(),
+                            table: table.name.as_str(),
+                            columns: fk.column_indices.iter().map(|i|table.columns[*i].name.as_str()).collect()
+                        });
+                    referenced_by.extend(references);
+                }
+            }
+            referenced_by.sort_by(|a: &ReferenceMetaInfo,b: &ReferenceMetaInfo|(a.schema, a.table, a.name).cmp(&(b.schema, b.table, b.name)));
+
             let response = TableMetaInfo {
                 name: info.name.as_str(),
                 is_view: info.is_view,
                 temporary: info.temporary,
                 has_pk: pk_indices.len() > 0,
-                columns
+                comment: info.comment.as_deref(),
+                columns,
+                foreign_keys,
+                referenced_by
             };
             return HttpResponse::Ok().json(response)
         }
@@ -120,3 +240,32 @@ async fn table_metainfo(path: web::Path<(String,String)>, data: web::Data<Arc<Ap
 
     HttpResponse::NotFound().finish()
 }
+
+#[get("/schemas/{schema}/rpc/")]
+async fn procedures_metainfo(path: web::Path<(String,)>, data: web::Data<Arc<ApplicationState>>) -> impl Responder {
+    let schema_name = path.into_inner().0;
+    let metainfo = data.metainfo.read().unwrap();
+
+    match metainfo.schemas.get(schema_name.as_str()) {
+        Some(info) => {
+            let mut procedures: Vec<ProcedureMetaInfo> = info.procedures.iter().map(|p|
+                ProcedureMetaInfo {
+                    package: p.package.as_deref(),
+                    name: p.name.as_str(),
+                    overload: p.overload.as_deref(),
+                    unsupported: p.unsupported,
+                    result: p.result.as_ref().map(|r|r.col_type_name),
+                    arguments: p.arguments.iter().map(|a| ArgumentMetaInfo {
+                        name: a.name.as_str(),
+                        col_type: a.col_type_name,
+                        direction: a.direction.as_str(),
+                        defaulted: a.defaulted
+                    }).collect()
+                }).collect();
+            procedures.sort_by(|a,b|(a.package, a.name, a.overload).cmp(&(b.package, b.name, b.overload)));
+
+            HttpResponse::Ok().json(ProceduresMetainfo { procedures })
+        },
+        None => HttpResponse::NotFound().finish()
+    }
+}
@@ -1,9 +1,19 @@
 mod mgmt_scope;
 mod api_scope;
+mod export_scope;
 mod query;
+mod filter;
+mod keyset;
+mod modification;
+mod encoding;
+mod expand;
+mod rpc;
+mod lob;
 
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::io::{Error, ErrorKind, Result};
+use std::time::Duration;
 
 // TODO: full static files support with NPM build
 // SEE:  https://crates.io/crates/actix-web-static-files
@@ -12,19 +22,29 @@ use std::io::{Error, ErrorKind, Result};
 // TODO: example with static files and R2D2: https://stackoverflow.com/questions/63653540/serving-static-files-with-actix-web-2-0
 
 use actix_web::{get, web, HttpResponse, Responder, Scope, HttpRequest};
+use actix_web::error::BlockingError;
 use actix_files as fs;
 
 use crate::config::Config;
+use crate::datasource::{RequestContext, SessionTrace};
 use crate::metainfo::{self, MetaInfo};
+use crate::security::SecurityContext;
 use actix_files::NamedFile;
 use std::path::PathBuf;
 
 pub use mgmt_scope::management_scope;
-pub use api_scope::api_scope;
+pub use api_scope::{api_scope, write_scope, rpc_scope};
+pub use export_scope::export_scope;
+pub use encoding::ValueFormats;
 
 // This struct represents state
 pub struct ApplicationState {
-    metainfo: RwLock<MetaInfo>
+    metainfo: RwLock<MetaInfo>,
+    // maximal execution time of requests in milliseconds by route pattern
+    timeouts:        HashMap<String, u32>,
+    default_timeout: u32,
+    // formats of decimal and binary values in results and parameters
+    formats:         encoding::ValueFormats,
 }
 
 impl ApplicationState {
@@ -32,10 +52,121 @@ impl ApplicationState {
         let metainfo = metainfo::MetaInfo::load(&conf.excludes)
             .map_err(|e|Error::new(ErrorKind::Other, e))?;
         let metainfo = RwLock::new(metainfo);
-        Ok( Arc::new(ApplicationState{metainfo}) )
+
+        let mut formats = encoding::ValueFormats::default();
+        if let Some(decimal) = conf.encoding.as_ref().and_then(|e|e.decimal.as_ref()) {
+            formats.decimal = encoding::DecimalFormat::parse(decimal)
+                .map_err(|e|Error::new(ErrorKind::Other, e))?;
+        }
+        if let Some(binary) = conf.encoding.as_ref().and_then(|e|e.binary.as_ref()) {
+            formats.binary = encoding::BinaryFormat::parse(binary)
+                .map_err(|e|Error::new(ErrorKind::Other, e))?;
+        }
+
+        let (timeouts, default_timeout) = match &conf.timeouts {
+            Some(t) => (t.routes.iter().map(|r|(r.path.to_owned(), r.timeout)).collect(), t.default.unwrap_or(0)),
+            None => (HashMap::new(), 0)
+        };
+
+        Ok( Arc::new(ApplicationState{metainfo, timeouts, default_timeout, formats}) )
+    }
+
+    /// maximal execution time of route in milliseconds, 0 is unlimited
+    fn timeout(&self, route: &str) -> u32 {
+        self.timeouts.get(route).copied().unwrap_or(self.default_timeout)
+    }
+}
+
+/// oracle sessions of request: tracing attributes, maximal execution time of route and formats of values
+fn request_context(req: &HttpRequest, state: &ApplicationState) -> RequestContext {
+    let route = req.match_pattern().unwrap_or_else(||req.path().to_string());
+    RequestContext {
+        trace: session_trace(req),
+        timeout: state.timeout(&route),
+        cancellation: Default::default(),
+        formats: state.formats
+    }
+}
+
+/// Failure of blocking function of request
+#[derive(Debug)]
+enum ExecutionError {
+    /// error of function or thread pool
+    Failed(BlockingError<String>),
+    /// execution time of request exceeded timeout in milliseconds
+    TimedOut(u32),
+}
+
+impl ExecutionError {
+    /// log error, response is 504 if time of request is exceeded, otherwise 500
+    fn response(&self) -> HttpResponse {
+        match self {
+            ExecutionError::Failed(err) => {
+                eprintln!("{:?}", err);
+                HttpResponse::InternalServerError().finish()
+            },
+            ExecutionError::TimedOut(timeout) => {
+                let message = format!("Execution time of request exceeded {} ms", timeout);
+                eprintln!("{}", message);
+                HttpResponse::GatewayTimeout().body(message)
+            }
+        }
+    }
+}
+
+/// execute blocking function of request in thread pool.
+/// calls of request are cancelled when request is dropped (client is disconnected) or its time is exceeded.
+/// on timeout the function is not awaited: it keeps running in thread pool until its current oracle call
+/// is interrupted by OCIBreak, then it fails with error of cancelled call, and its result is discarded
+async fn execute_blocking<F, T>(context: &RequestContext, f: F) -> std::result::Result<T, ExecutionError>
+    where F: FnOnce() -> std::result::Result<T, String> + Send + 'static,
+          T: Send + 'static {
+    let _guard = context.cancellation.guard();
+
+    if context.timeout == 0 {
+        return web::block(f).await.map_err(ExecutionError::Failed);
+    }
+
+    let timeout = Duration::from_millis(context.timeout as u64);
+    match actix_web::rt::time::timeout(timeout, web::block(f)).await {
+        Ok(result) => result.map_err(ExecutionError::Failed),
+        Err(_) => Err(ExecutionError::TimedOut(context.timeout))
     }
 }
 
+/// maximal length of tracing attributes of oracle session in bytes
+const MAX_TRACE_LEN: usize = 64;
+
+/// tracing attributes of oracle session for request:
+/// subject of JWT as client identifier, matched route as action and address of client as client info
+fn session_trace(req: &HttpRequest) -> SessionTrace {
+    let subject = req.extensions()
+        .get::<SecurityContext>()
+        .map(|ctx|ctx.subject().to_string())
+        .unwrap_or_default();
+    let route = req.match_pattern().unwrap_or_else(||req.path().to_string());
+    let address = req.connection_info().realip_remote_addr().unwrap_or_default().to_string();
+
+    SessionTrace {
+        client_identifier: truncate_trace(subject),
+        module: env!("CARGO_PKG_NAME").to_string(),
+        action: truncate_trace(format!("{} {}", req.method(), route)),
+        client_info: truncate_trace(address)
+    }
+}
+
+/// truncate value to maximal length on char boundary
+fn truncate_trace(mut value: String) -> String {
+    if value.len() > MAX_TRACE_LEN {
+        let mut len = MAX_TRACE_LEN;
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+        value.truncate(len);
+    }
+    value
+}
+
 // group of base endpoints
 pub fn base_scope() -> Scope {
     web::scope("/")
@@ -57,3 +188,26 @@ async fn index() -> Result<NamedFile> {
 async fn health() -> impl Responder {
     "OK".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_trace_to_limit() {
+        assert_eq!(truncate_trace("short".to_string()), "short");
+        assert_eq!(truncate_trace("a".repeat(MAX_TRACE_LEN)).len(), MAX_TRACE_LEN);
+        assert_eq!(truncate_trace("a".repeat(MAX_TRACE_LEN + 1)).len(), MAX_TRACE_LEN);
+    }
+
+    #[test]
+    fn truncate_trace_on_char_boundary() {
+        // 2-byte character crosses the limit, it is removed entirely
+        let value = format!("{}é", "a".repeat(MAX_TRACE_LEN - 1));
+        assert_eq!(truncate_trace(value), "a".repeat(MAX_TRACE_LEN - 1));
+
+        // 3-byte characters: 21 of them fit into 64 bytes
+        let truncated = truncate_trace("€".repeat(MAX_TRACE_LEN));
+        assert_eq!(truncated, "€".repeat(MAX_TRACE_LEN / 3));
+    }
+}
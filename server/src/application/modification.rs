@@ -0,0 +1,260 @@
+use serde_json::{Map, Value};
+
+use crate::{metainfo as mi, datasource};
+use crate::datasource::RequestContext;
+use crate::application::query::{ColTypeInfo, DirectedParameter, DirectedParamsProvider, DynamicParamsProvider, ParsedParameter, parse_pk_params};
+use crate::application::encoding::{self, BinaryFormat, ColumnValue, ResultFormat, ValueFormats};
+
+/// Data modification statement (INSERT, UPDATE, DELETE) generated from table metainfo
+pub struct DynamicModification {
+    sql:           String,
+    param_columns: Vec<ColTypeInfo>,
+    parsed_params: Vec<ParsedParameter>,
+    // columns of RETURNING clause, they are binded after parameters
+    returning:     Vec<(String, ColTypeInfo)>,
+    context:       RequestContext,
+}
+
+/// Result of modification: count of affected rows and returned columns as json object
+pub struct ModificationResult {
+    pub row_count: u64,
+    pub returned:  Option<Vec<u8>>,
+}
+
+/// parsed column values from json body: column names, types and values
+type ParsedValues = (Vec<String>, Vec<ColTypeInfo>, Vec<ParsedParameter>);
+
+impl DynamicModification {
+    /// INSERT INTO table (c1,c2) VALUES (:1,:2) RETURNING pk1 INTO :3
+    /// primary key is returned, so generated keys (identity, sequence in trigger) are known
+    pub fn create_insert(schema_name: &str, table_info: &mi::TableInfo, values: Map<String,Value>, binary: BinaryFormat) -> Result<DynamicModification, String> {
+        check_modifiable(table_info)?;
+        if values.is_empty() {
+            return Err("No column values for insert".to_string());
+        }
+
+        let (column_names, param_columns, parsed_params) = parse_values(table_info, values, binary)?;
+
+        let placeholders: Vec<String> = (1..=column_names.len()).map(|idx|format!(":{}", idx)).collect();
+
+        let mut sql = format!("INSERT INTO {}.{} ({}) VALUES ({})",
+                              schema_name, table_info.name, column_names.join(","), placeholders.join(","));
+
+        let mut returning = Vec::new();
+        if let Some(pk) = &table_info.primary_key {
+            returning = pk.column_indices
+                .iter()
+                .map(|idx|&table_info.columns[*idx])
+                .map(|c|(c.name.to_owned(), ColTypeInfo::new(c)))
+                .collect();
+
+            let names: Vec<&str> = returning.iter().map(|(name, _)|name.as_str()).collect();
+            let into: Vec<String> = (1..=names.len()).map(|idx|format!(":{}", column_names.len() + idx)).collect();
+            sql.push_str(&format!(" RETURNING {} INTO {}", names.join(","), into.join(",")));
+        }
+
+        Ok( DynamicModification { sql, param_columns, parsed_params, returning, context: RequestContext::default() } )
+    }
+
+    /// UPDATE table SET c1 = :1, c2 = :2 WHERE pk1 = :3
+    /// if full update (PUT), all non-pk columns absent in body are set to NULL
+    pub fn create_update(schema_name: &str,
+                         table_info:  &mi::TableInfo,
+                         pk_params:   Vec<String>,
+                         values:      Map<String,Value>,
+                         full:        bool,
+                         binary:      BinaryFormat
+    ) -> Result<DynamicModification, String> {
+        check_modifiable(table_info)?;
+        let (pk_column_names, pk_columns, pk_params) = parse_pk_params(table_info, pk_params, binary)?;
+
+        if let Some(name) = values.keys().find(|name|pk_column_names.contains(name)) {
+            return Err(format!("Primary key column {} can not be updated", name));
+        }
+
+        let (mut column_names, mut param_columns, mut parsed_params) = parse_values(table_info, values, binary)?;
+
+        if full {
+            let absent: Vec<&mi::ColumnInfo> = table_info.columns
+                .iter()
+                .filter(|c|!pk_column_names.contains(&c.name) && !column_names.contains(&c.name) && !c.is_lob())
+                .collect();
+
+            for column in absent {
+                if !column.nullable {
+                    return Err(format!("Column {} is not nullable and must have value", column.name));
+                }
+                column_names.push(column.name.to_owned());
+                param_columns.push(ColTypeInfo::new(column));
+                parsed_params.push(ParsedParameter::Null);
+            }
+        }
+
+        if column_names.is_empty() {
+            return Err("No column values for update".to_string());
+        }
+
+        let set_columns: Vec<String> =
+            column_names.iter().enumerate().map(|(idx,name)|format!("{} = :{}", name, idx+1)).collect();
+        let where_columns = enumerate_pk_columns(&pk_column_names, column_names.len());
+
+        let sql = format!("UPDATE {}.{} SET {} WHERE {}",
+                          schema_name, table_info.name, set_columns.join(", "), where_columns);
+
+        param_columns.extend(pk_columns);
+        parsed_params.extend(pk_params);
+
+        Ok( DynamicModification { sql, param_columns, parsed_params, returning: vec![], context: RequestContext::default() } )
+    }
+
+    /// DELETE FROM table WHERE pk1 = :1
+    pub fn create_delete(schema_name: &str, table_info: &mi::TableInfo, pk_params: Vec<String>, binary: BinaryFormat) -> Result<DynamicModification, String> {
+        check_modifiable(table_info)?;
+        let (pk_column_names, param_columns, parsed_params) = parse_pk_params(table_info, pk_params, binary)?;
+
+        let sql = format!("DELETE FROM {}.{} WHERE {}",
+                          schema_name, table_info.name, enumerate_pk_columns(&pk_column_names, 0));
+
+        Ok( DynamicModification { sql, param_columns, parsed_params, returning: vec![], context: RequestContext::default() } )
+    }
+
+    /// tracing attributes, timeout and cancellation of oracle session
+    pub fn with_context(mut self, context: RequestContext) -> DynamicModification {
+        self.context = context;
+        self
+    }
+
+    /// execute a statement and commit transaction, it is rolled back on error
+    pub fn execute(self) -> Result<ModificationResult,String> {
+        let conn = datasource::get_request_connection(&self.context)
+            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+        let tx = conn.begin();
+
+        let result = if self.returning.is_empty() {
+            execute_statement(&tx, &self.sql, self.param_columns, self.parsed_params)
+                .map(|row_count|ModificationResult { row_count, returned: None })?
+        } else {
+            execute_returning(&tx, &self.sql, self.param_columns, self.parsed_params, self.returning, self.context.formats)?
+        };
+
+        tx.commit()
+            .map_err(|err|format!("Can not commit transaction: {}", err))?;
+        Ok(result)
+    }
+}
+
+fn execute_statement(conn: &oracle::Connection, sql: &str, param_columns: Vec<ColTypeInfo>, parsed_params: Vec<ParsedParameter>) -> Result<u64, String> {
+    let params_provider = Box::new( DynamicParamsProvider { columns: param_columns });
+
+    let stmt = conn.prepare_dynamic(sql, params_provider)
+        .map_err(|err|format!("Can not prepare statement: {}", err))?;
+
+    stmt.execute(parsed_params)
+        .map_err(|err|format!("Can not execute statement: {}", err))
+}
+
+/// execute statement with RETURNING clause, returned columns are OUT parameters after input parameters
+fn execute_returning(conn:          &oracle::Connection,
+                     sql:           &str,
+                     param_columns: Vec<ColTypeInfo>,
+                     mut parsed_params: Vec<ParsedParameter>,
+                     returning:     Vec<(String, ColTypeInfo)>,
+                     formats:       ValueFormats
+) -> Result<ModificationResult, String> {
+    let inputs = param_columns.len();
+
+    let mut columns: Vec<DirectedParameter> = param_columns
+        .into_iter()
+        .map(|column|DirectedParameter { column, direction: oracle::Direction::In })
+        .collect();
+    for (_, column) in &returning {
+        columns.push( DirectedParameter { column: *column, direction: oracle::Direction::Out });
+        parsed_params.push(ParsedParameter::Null);
+    }
+
+    let params_provider = Box::new( DirectedParamsProvider { columns });
+
+    let stmt = conn.prepare_dynamic(sql, params_provider)
+        .map_err(|err|format!("Can not prepare statement: {}", err))?;
+
+    let out = stmt.execute_out(parsed_params)
+        .map_err(|err|format!("Can not execute statement: {}", err))?;
+
+    let values: Vec<ColumnValue> = returning
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, column))|ColumnValue::from_result(&out.value(inputs + idx), column.col_type))
+        .collect();
+    let names: Vec<String> = returning.into_iter().map(|(name, _)|name).collect();
+
+    let mut returned = Vec::new();
+    encoding::write_row(&mut returned, ResultFormat::Json, formats, &names, &values);
+
+    Ok( ModificationResult { row_count: out.row_count(), returned: Some(returned) } )
+}
+
+/// views are read only, even if they are updatable in oracle
+fn check_modifiable(table_info: &mi::TableInfo) -> Result<(), String> {
+    if table_info.is_view {
+        return Err(format!("{} is a view and can not be modified", table_info.name));
+    }
+    Ok(())
+}
+
+/// parse json object with column values, check columns and nullability with metainfo
+fn parse_values(table_info: &mi::TableInfo, values: Map<String,Value>, binary: BinaryFormat) -> Result<ParsedValues, String> {
+    let columns_len = values.len();
+
+    let mut column_names = Vec::with_capacity(columns_len);
+    let mut param_columns = Vec::with_capacity(columns_len);
+    let mut parsed_params = Vec::with_capacity(columns_len);
+
+    for (col_name, value) in values {
+        let column = table_info.columns
+            .iter()
+            .find(|c|c.name == col_name)
+            .ok_or_else(||format!("Not found column {}", col_name))?;
+
+        let param_column = ColTypeInfo::new(column);
+        let parsed = ParsedParameter::parse_json(&param_column, &value, binary)
+            .map_err(|err|format!("Can not parse value {} for column {}: {}", value, col_name, err))?;
+
+        if parsed.is_null() && !column.nullable {
+            return Err(format!("Column {} is not nullable", col_name));
+        }
+
+        parsed_params.push(parsed);
+        param_columns.push(param_column);
+        column_names.push(col_name);
+    }
+
+    Ok((column_names, param_columns, parsed_params))
+}
+
+/// where clause for primary key, placeholders are numbered after skipped parameters
+pub(super) fn enumerate_pk_columns(pk_column_names: &[String], skip: usize) -> String {
+    let enumerated: Vec<String> =
+        pk_column_names.iter().enumerate().map(|(idx,name)|format!("{} = :{}", name, skip+idx+1)).collect();
+    enumerated.join(" AND ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use oracle::SqlType;
+
+    #[test]
+    fn views_are_read_only() {
+        let mut info = mi::TableInfo::for_test("items_v", &[("id", SqlType::Int64, false), ("name", SqlType::Varchar, true)]);
+        info.is_view = true;
+        let values = || json!({"name": "A"}).as_object().unwrap().clone();
+
+        assert!(DynamicModification::create_insert("s", &info, values(), BinaryFormat::Hex).is_err());
+        assert!(DynamicModification::create_update("s", &info, vec!["1".to_string()], values(), false, BinaryFormat::Hex).is_err());
+        assert!(DynamicModification::create_delete("s", &info, vec!["1".to_string()], BinaryFormat::Hex).is_err());
+
+        info.is_view = false;
+        assert!(DynamicModification::create_insert("s", &info, values(), BinaryFormat::Hex).is_ok());
+    }
+}
@@ -1,128 +1,178 @@
 use oracle::{self, ValueProjector};
 use crate::{metainfo as mi, datasource};
-use std::collections::HashMap;
+use crate::datasource::RequestContext;
+use crate::application::filter::{Predicate, FilterParams};
+use crate::application::keyset::{self, Keyset};
+use crate::application::encoding::{self, BinaryFormat, ResultFormat, ColumnValue};
+use crate::application::expand::{self, Expansion};
+use chrono::{DateTime, Local};
 
 pub struct DynamicQuery {
     table_name:    String,
     columns:       Vec<ColTypeInfo>,
     column_names:  Vec<String>,
+    result_names:  Vec<String>,
 
-    param_columns:      Vec<ColTypeInfo>,
-    param_column_names: Vec<String>,
-    parsed_params:      Vec<ParsedParameter>,
+    predicate: Predicate,
+    // condition of keyset pagination after cursor, it does not filter count of rows
+    seek_predicate: Option<Predicate>,
 
     limit:  u16,
     offset: Option<u16>,
     order_column_names: Vec<String>,
+
+    // positions of ordering key columns in selected columns for keyset pagination
+    key_indices: Option<Vec<usize>>,
+
+    count:          Option<CountMode>,
+    estimated_rows: i64,
+
+    format:     ResultFormat,
+    expansions: Vec<Expansion>,
+    context:    RequestContext,
+}
+
+/// rows prefetched by one round-trip while export
+const EXPORT_PREFETCH_ROWS: usize = 500;
+/// size of chunk of exported data sent to client
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Format of exported rows: newline delimited JSON, JSON array, CSV or XML
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    NdJson, Json, Csv, Xml
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Result<ExportFormat, String> {
+        match value {
+            "ndjson" => Ok(ExportFormat::NdJson),
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "xml" => Ok(ExportFormat::Xml),
+            _ => Err(format!("Invalid export format '{}', must be ndjson, json, csv or xml", value))
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::NdJson => "application/x-ndjson",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => ResultFormat::Csv.content_type(),
+            ExportFormat::Xml => ResultFormat::Xml.content_type(),
+        }
+    }
+}
+
+/// Paging parameters of list query
+pub struct Paging {
+    pub limit:  Option<u16>,
+    pub offset: Option<u16>,
+    pub cursor: Option<String>,
+    pub count:  Option<CountMode>,
+}
+
+/// Count of rows matched by query: exact runs `SELECT COUNT(*)` in parallel with query,
+/// estimated is taken from optimizer statistics of table and is null if query has a filter
+#[derive(Clone, Copy, PartialEq)]
+pub enum CountMode {
+    Exact, Estimated
+}
+
+impl CountMode {
+    pub fn parse(value: &str) -> Result<CountMode, String> {
+        match value {
+            "exact" => Ok(CountMode::Exact),
+            "estimated" => Ok(CountMode::Estimated),
+            _ => Err(format!("Invalid count mode '{}', must be exact or estimated", value))
+        }
+    }
+}
+
+/// Page of list query: encoded rows, count of rows and cursor or offset of next page
+pub struct ResultPage {
+    pub body:  Vec<u8>,
+    pub total: Option<i64>,
+    pub next:  Option<String>,
 }
 
 struct DynamicResultsProvider {
-    column_names: Vec<String>,
-    columns:      Vec<ColTypeInfo>
+    columns:      Vec<ColTypeInfo>,
+    key_indices:  Vec<usize>,
+}
+
+/// Row of dynamic query: typed values of selected columns and values of ordering key
+pub struct DynamicRow {
+    values: Vec<ColumnValue>,
+    key:    Vec<String>,
+}
+
+pub struct DynamicParamsProvider {
+    pub columns: Vec<ColTypeInfo>
+}
+
+/// Parameter with direction, OUT parameters are used in PL/SQL calls and RETURNING clause
+#[derive(Clone, Copy)]
+pub struct DirectedParameter {
+    pub column:    ColTypeInfo,
+    pub direction: oracle::Direction,
 }
 
-struct DynamicParamsProvider {
-    columns: Vec<ColTypeInfo>
+pub struct DirectedParamsProvider {
+    pub columns: Vec<DirectedParameter>
 }
 
-enum ParsedParameter {
-    Int16 (i16), Int32(i32), Int64(i64), Varchar(String)
+#[derive(Clone)]
+pub enum ParsedParameter {
+    Int16 (i16), Int32(i32), Int64(i64), Float64(f64), Decimal(oracle::SqlDecimal), Varchar(String), Raw(Vec<u8>), DateTime(oracle::SqlDateTime),
+    Timestamp(oracle::SqlTimestamp), TimestampTz(oracle::SqlTimestampTz),
+    IntervalYM(oracle::SqlIntervalYM), IntervalDS(oracle::SqlIntervalDS), Null
 }
 
-struct ColTypeInfo {
+#[derive(Clone, Copy)]
+pub struct ColTypeInfo {
     pub col_type:      oracle::SqlType,
     pub oci_data_type: oracle::TypeDescriptor,
     pub nullable:      bool
 }
 
 impl ColTypeInfo {
-    fn new(info: &mi::ColumnInfo) -> ColTypeInfo {
+    pub fn new(info: &mi::ColumnInfo) -> ColTypeInfo {
         ColTypeInfo { col_type: info.col_type, oci_data_type: info.oci_data_type, nullable: info.nullable }
     }
+
+    pub fn for_argument(info: &mi::ArgumentInfo) -> ColTypeInfo {
+        ColTypeInfo { col_type: info.col_type, oci_data_type: info.oci_data_type, nullable: true }
+    }
 }
 
 impl DynamicQuery {
-    pub fn create_from_pk(schema_name: &str, table_info: &mi::TableInfo, pk_params: Vec<String>) -> Result<DynamicQuery, String> {
-        match &table_info.primary_key {
-            None => Err("Primary key not exists".to_string()),
-            Some(pk) => {
-                let pk_indices = &pk.column_indices;
-
-                let param_columns_len = pk_params.len();
-
-                if param_columns_len != pk_indices.len() {
-                    return Err("Count of columns in primary key does not match with count of parameters in query".to_string())
-                }
-
-                let columns: Vec<ColTypeInfo> = table_info.columns.iter().map(ColTypeInfo::new).collect();
-                let column_names: Vec<&str> = table_info.columns.iter().map(|c|c.name.as_str()).collect();
+    pub fn create_from_pk(schema_name: &str, table_info: &mi::TableInfo, pk_params: Vec<String>, select: Vec<String>, binary: BinaryFormat) -> Result<DynamicQuery, String> {
+        let (param_column_names, param_columns, parsed_params) = parse_pk_params(table_info, pk_params, binary)?;
+        let predicate = Predicate::all_equal(param_column_names, param_columns, parsed_params);
 
-                let mut param_column_names = Vec::with_capacity(param_columns_len);
-                let mut param_columns = Vec::with_capacity(param_columns_len);
-                let mut parsed_params = Vec::with_capacity(param_columns_len);
+        let (columns, column_names, result_names) = select_columns(table_info, select)?;
 
-                for (pk_column_index, p) in pk.column_indices.iter().zip(pk_params) {
-                    let pk_column = unsafe { table_info.columns.get_unchecked(*pk_column_index) };
-
-                    let parsed = ParsedParameter::parse(pk_column.col_type, p.to_string());
-                    match parsed {
-                        Err(err) => return Err(format!("Can not parse parameter value {} for column {}: {}", p, pk_column.name, err)),
-                        Ok(parsed) => {
-                            parsed_params.push(parsed);
-                            param_columns.push(ColTypeInfo::new( pk_column ));
-                            param_column_names.push(pk_column.name.to_owned());
-                        }
-                    }
-                };
-
-                let table_name = format!("{}.{}", schema_name, table_info.name.as_str());
-                let column_names = column_names.iter().map(|name|name.to_string()).collect();
+        let table_name = format!("{}.{}", schema_name, table_info.name.as_str());
 
-                let limit = 1;
-                let offset = Option::None;
+        let limit = 1;
+        let offset = Option::None;
 
-                Ok( DynamicQuery {
-                    table_name, columns, column_names,
-                    param_columns, param_column_names, parsed_params,
-                    limit, offset, order_column_names: vec![] } )
-            }
-        }
+        Ok( DynamicQuery {
+            table_name, columns, column_names, result_names, predicate, seek_predicate: None,
+            limit, offset, order_column_names: vec![], key_indices: None,
+            count: None, estimated_rows: 0, format: ResultFormat::Json, expansions: vec![],
+            context: RequestContext::default() } )
     }
 
     pub fn create_from_params(schema_name: &str,
                               table_info:  &mi::TableInfo,
-                              parameters:  HashMap<String,String>,
+                              predicate:   Predicate,
+                              select:      Vec<String>,
                               order:       Vec<String>,
-                              limit:       Option<u16>,
-                              offset:      Option<u16>
+                              paging:      Paging
     ) -> Result<DynamicQuery, String> {
-        let columns: Vec<ColTypeInfo> = table_info.columns.iter().map(ColTypeInfo::new).collect();
-        let column_names: Vec<&str> = table_info.columns.iter().map(|c|c.name.as_str()).collect();
-
-        let param_columns_len = parameters.len();
-
-        let mut param_column_names = Vec::with_capacity(param_columns_len);
-        let mut param_columns = Vec::with_capacity(param_columns_len);
-        let mut parsed_params = Vec::with_capacity(param_columns_len);
-
-        for (ref col_name,ref p) in parameters {
-            let column = table_info.columns.iter().find(|c|&c.name == col_name);
-
-            match column {
-                None => return Err(format!("Not found column {}", col_name)),
-                Some(column) => {
-                    let parsed = ParsedParameter::parse(column.col_type, p.to_string());
-                    match parsed {
-                        Err(err) => return Err(format!("Can not parse parameter value {} for column {}: {}", p, col_name, err)),
-                        Ok(parsed) => {
-                            parsed_params.push(parsed);
-                            param_columns.push(ColTypeInfo::new( column ));
-                            param_column_names.push(col_name.to_owned());
-                        }
-                    }
-                }
-            }
-        }
+        let (mut columns, mut column_names, result_names) = select_columns(table_info, select)?;
 
         let table_name = format!("{}.{}", schema_name, table_info.name.as_str());
 
@@ -133,7 +183,8 @@ impl DynamicQuery {
             }
         };
 
-        let column_names = column_names.iter().map(|name|name.to_string()).collect();
+        let Paging { limit, offset, cursor, count } = paging;
+        let estimated_rows = table_info.num_rows as i64;
 
         let limit = limit.unwrap_or(25);
 
@@ -141,6 +192,30 @@ impl DynamicQuery {
             return Err("limit rows must be <= 100".to_string());
         }
 
+        if cursor.is_some() {
+            if offset.is_some() || !order.is_empty() {
+                return Err("offset and order are not supported with keyset pagination (cursor)".to_string());
+            }
+
+            let keyset = Keyset::for_table(table_info)?;
+
+            let seek_predicate = match cursor.as_deref() {
+                None | Some("") => None,
+                Some(cursor) => Some(keyset.seek_predicate(cursor)?)
+            };
+
+            let key_indices = keyset.column_indices
+                .iter()
+                .map(|idx|select_hidden(&mut columns, &mut column_names, &table_info.columns[*idx]))
+                .collect();
+
+            return Ok( DynamicQuery {
+                table_name, columns, column_names, result_names, predicate, seek_predicate,
+                limit, offset, order_column_names: keyset.column_names, key_indices: Some(key_indices),
+                count, estimated_rows, format: ResultFormat::Json, expansions: vec![],
+            context: RequestContext::default() } );
+        }
+
         if let Some(offset) = offset {
             if offset < limit {
                 return Err("offset must be >= limit".to_string());
@@ -151,49 +226,84 @@ impl DynamicQuery {
         }
 
         Ok( DynamicQuery {
-            table_name, columns, column_names,
-            param_columns, param_column_names, parsed_params,
-            limit, offset, order_column_names: order } )
+            table_name, columns, column_names, result_names, predicate, seek_predicate: None,
+            limit, offset, order_column_names: order, key_indices: None,
+            count, estimated_rows, format: ResultFormat::Json, expansions: vec![],
+            context: RequestContext::default() } )
     }
 
-    fn generate_sql(&self) -> String {
-        let joined_result_columns = self.column_names.join(",");
-
-        let enumerated_param_columns: Vec<String> =
-            self.param_column_names.iter().enumerate().map(|(idx,name)|format!("{} = :{}", name, idx+1)).collect();
-        let joined_param_columns = enumerated_param_columns.join(" AND ");
+    /// query for export of all rows, without limit and offset
+    pub fn create_for_export(schema_name: &str,
+                             table_info:  &mi::TableInfo,
+                             predicate:   Predicate,
+                             select:      Vec<String>,
+                             order:       Vec<String>
+    ) -> Result<DynamicQuery, String> {
+        let (columns, column_names, result_names) = select_columns(table_info, select)?;
 
-        format!("SELECT {} FROM {} WHERE {}", joined_result_columns, self.table_name, joined_param_columns)
-    }
+        let table_name = format!("{}.{}", schema_name, table_info.name.as_str());
 
-    /// execute a query and generate JSON result
-    pub fn fetch_one(self) -> Result<String,String> {
-        let conn = datasource::get_connection()
-            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+        for col_name in &order {
+            if table_info.columns.iter().find(|c|&c.name == col_name).is_none() {
+                return Err(format!("Order column {} nof found in table {}", col_name, &table_name))
+            }
+        };
 
-        let (query, params) = self.prepare_query(&conn, 1)?;
+        Ok( DynamicQuery {
+            table_name, columns, column_names, result_names, predicate, seek_predicate: None,
+            limit: 0, offset: None, order_column_names: order, key_indices: None,
+            count: None, estimated_rows: 0, format: ResultFormat::Json, expansions: vec![],
+            context: RequestContext::default() } )
+    }
 
-        let result = query.fetch_one(params)
-            .map_err(|err|format!("Can not fetch row by pk: {}", err))?;
+    /// format of result rows, JSON by default
+    pub fn with_format(mut self, format: ResultFormat) -> DynamicQuery {
+        self.format = format;
+        self
+    }
 
-        Ok( result.map_or("{}".to_string(), |r| format!("{}", r))  )
+    /// tracing attributes, timeout and cancellation of oracle session
+    pub fn with_context(mut self, context: RequestContext) -> DynamicQuery {
+        self.context = context;
+        self
     }
 
-    /// execute a query and generate JSON result
-    pub fn fetch_many(self) -> Result<String,String> {
-        let conn = datasource::get_connection()
-            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+    /// embed related rows by `expand` parameter, see `expand` module
+    pub fn with_expand<'a>(mut self, metainfo: &'a mi::MetaInfo, schema_name: &str, table_info: &'a mi::TableInfo, expand: Vec<String>) -> Result<DynamicQuery, String> {
+        if expand.is_empty() {
+            return Ok(self);
+        }
+        if self.format == ResultFormat::Csv {
+            return Err("expand is not supported for CSV format".to_string());
+        }
 
-        let (query, params) = self.prepare_query(&conn, 25)?;
+        self.expansions = expand::parse_expand(metainfo, schema_name, table_info, expand, &mut self.columns, &mut self.column_names)?;
+
+        if let Some(expansion) = self.expansions.iter().find(|e|self.result_names.contains(&e.name)) {
+            return Err(format!("Name of relation '{}' is same as selected column", expansion.name));
+        }
+        Ok(self)
+    }
 
-        let result = query.fetch_list(params)
-            .map_err(|err|format!("Can not fetch row by where clause: {}", err))?.join(",");
+    /// names of result columns and expanded relations
+    fn output_names(&self) -> Vec<String> {
+        let mut names = self.result_names.clone();
+        names.extend(self.expansions.iter().map(|e|e.name.to_owned()));
+        names
+    }
 
-        Ok( format!("[{}]", result) )
+    /// generate where clause with binded parameters, seek predicate of keyset pagination is appended to filter
+    fn generate_condition(&mut self) -> (String, FilterParams) {
+        let mut predicate = std::mem::replace(&mut self.predicate, Predicate::And(vec![]));
+        if let Some(seek_predicate) = self.seek_predicate.take() {
+            predicate = Predicate::And(vec![predicate, seek_predicate]);
+        }
+        where_clause(predicate)
     }
 
-    fn prepare_query<'conn>(self, conn: &'conn oracle::Connection, prefetch_rows: usize) -> Result<(oracle::Query<'conn, Vec<ParsedParameter>, String>, Vec<ParsedParameter>), String> {
-        let mut sql = self.generate_sql();
+    fn generate_sql(&self, condition: &str, fetch_rows: Option<u16>) -> String {
+        let joined_result_columns = self.column_names.join(",");
+        let mut sql = format!("SELECT {} FROM {}{}", joined_result_columns, self.table_name, condition);
 
         if self.order_column_names.len() > 0 {
             let joined_order_columns = self.order_column_names.join(",");
@@ -201,50 +311,455 @@ impl DynamicQuery {
             sql.push_str(&order_clause);
         }
 
-        if self.limit > 1 {
+        if let Some(fetch_rows) = fetch_rows {
             if let Some(offset) = self.offset {
                 let offset_clause = format!(" OFFSET {} ROWS", offset);
                 sql.push_str(&offset_clause);
             }
-            let fetch_clause = format!(" FETCH NEXT {} ROWS ONLY", self.limit);
+            let fetch_clause = format!(" FETCH NEXT {} ROWS ONLY", fetch_rows);
             sql.push_str(&fetch_clause);
         }
 
+        sql
+    }
+
+    /// execute a query and generate result in requested format
+    pub fn fetch_one(mut self) -> Result<Vec<u8>,String> {
+        let conn = datasource::get_request_connection(&self.context)
+            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+
+        let format = self.format;
+        let formats = self.context.formats;
+        let names = self.output_names();
+        let result_len = self.result_names.len();
+        let expansions = std::mem::take(&mut self.expansions);
+
+        let (condition, params) = self.generate_condition();
+        let sql = self.generate_sql(&condition, None);
+        let (query, params) = self.prepare_query(&conn, &sql, params, 1)?;
+
+        let result = query.fetch_one(params)
+            .map_err(|err|format!("Can not fetch row by pk: {}", err))?;
+
+        let mut rows: Vec<Vec<ColumnValue>> = result.into_iter().map(|row|row.values).collect();
+        expand::expand_rows(&conn, &expansions, &mut rows, result_len)?;
+
+        let mut body = Vec::new();
+        match format {
+            ResultFormat::Csv => encoding::write_csv_header(&mut body, &names),
+            ResultFormat::Xml => body.extend_from_slice(encoding::XML_DECLARATION.as_bytes()),
+            ResultFormat::Json => {}
+        }
+        match rows.first() {
+            Some(values) => encoding::write_row(&mut body, format, formats, &names, values),
+            None if format == ResultFormat::Json => body.extend_from_slice(b"{}"),
+            None if format == ResultFormat::Xml => body.extend_from_slice(b"<row/>"),
+            None => {}
+        }
+        Ok(body)
+    }
+
+    /// execute a query and generate result in requested format.
+    /// JSON result with keyset pagination or count of rows is an envelope:
+    /// `{"items":[...],"total":...,"next":...}`, where next is a cursor or offset of next page.
+    /// CSV result has a header row, XML result is `<rows total=".." next=".."><row>...</row></rows>`
+    pub fn fetch_many(mut self) -> Result<ResultPage,String> {
+        let conn = datasource::get_request_connection(&self.context)
+            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+
+        let format = self.format;
+        let formats = self.context.formats;
+        let names = self.output_names();
+        let result_len = self.result_names.len();
+        let expansions = std::mem::take(&mut self.expansions);
+        let keyset = self.key_indices.is_some();
+        let envelope = keyset || self.count.is_some();
+        let limit = self.limit as usize;
+        let next_offset = self.offset.unwrap_or(0) as usize + limit;
+
+        // exact count of rows is executed in parallel with query in second session of the request.
+        // rows are counted by filter only, without seek predicate of keyset pagination
+        let counter = match self.count {
+            Some(CountMode::Exact) => {
+                let (count_condition, count_params) = where_clause(self.predicate.clone());
+                let count_sql = format!("SELECT COUNT(*) FROM {}{}", self.table_name, count_condition);
+                let context = self.context.clone();
+                Some(std::thread::spawn(move || {
+                    let conn = datasource::get_request_connection(&context)
+                        .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+                    count_rows(&conn, &count_sql, count_params)
+                }))
+            },
+            _ => None
+        };
+        // estimate of table statistics is not a count of filtered rows, so it is omitted with filter
+        let estimated = match self.count {
+            Some(CountMode::Estimated) if self.predicate.is_empty() => Some(self.estimated_rows),
+            _ => None
+        };
+
+        let (condition, params) = self.generate_condition();
+
+        // in envelope one extra row is fetched to detect existence of next page
+        let fetch_rows = if envelope { self.limit + 1 } else { self.limit };
+        let sql = self.generate_sql(&condition, Some(fetch_rows));
+
+        let (query, params) = self.prepare_query(&conn, &sql, params, 25)?;
+
+        let mut rows = query.fetch_list(params)
+            .map_err(|err|format!("Can not fetch row by where clause: {}", err))?;
+
+        let total = match counter {
+            Some(counter) => Some(counter.join().map_err(|_|"Count of rows failed".to_string())??),
+            None => estimated
+        };
+
+        let has_next = rows.len() > limit;
+        rows.truncate(limit);
+
+        let next = match rows.last() {
+            Some(row) if has_next && keyset => Some(keyset::encode_cursor(&row.key)),
+            Some(_) if has_next => Some(next_offset.to_string()),
+            _ => None
+        };
+
+        let mut rows: Vec<Vec<ColumnValue>> = rows.into_iter().map(|row|row.values).collect();
+        expand::expand_rows(&conn, &expansions, &mut rows, result_len)?;
+
+        let mut body = Vec::new();
+        match format {
+            ResultFormat::Json if envelope => body.extend_from_slice(b"{\"items\":["),
+            ResultFormat::Json => body.push(b'['),
+            ResultFormat::Csv => encoding::write_csv_header(&mut body, &names),
+            ResultFormat::Xml => {
+                body.extend_from_slice(encoding::XML_DECLARATION.as_bytes());
+                body.extend_from_slice(b"<rows");
+                if let Some(total) = total {
+                    body.extend_from_slice(format!(" total=\"{}\"", total).as_bytes());
+                }
+                if let Some(next) = &next {
+                    body.extend_from_slice(format!(" next=\"{}\"", next).as_bytes());
+                }
+                body.push(b'>');
+            }
+        }
+
+        for (idx, values) in rows.iter().enumerate() {
+            if idx > 0 && format == ResultFormat::Json {
+                body.push(b',');
+            }
+            encoding::write_row(&mut body, format, formats, &names, values);
+        }
+
+        match format {
+            ResultFormat::Json if envelope => {
+                body.extend_from_slice(b"],\"total\":");
+                encoding::write_json(&mut body, &total);
+                body.extend_from_slice(b",\"next\":");
+                // cursor is a string, offset of next page is a number
+                match &next {
+                    Some(cursor) if keyset => encoding::write_json(&mut body, cursor),
+                    Some(_) => encoding::write_json(&mut body, &next_offset),
+                    None => body.extend_from_slice(b"null"),
+                }
+                body.push(b'}');
+            },
+            ResultFormat::Json => body.push(b']'),
+            ResultFormat::Csv => {},
+            ResultFormat::Xml => body.extend_from_slice(b"</rows>"),
+        }
+
+        Ok( ResultPage { body, total, next } )
+    }
+
+    /// execute a query and send all rows by chunks to sink, memory is bounded by size of chunk.
+    /// sink returns false if consumer is gone (client disconnected), then export is stopped
+    pub fn export<F>(mut self, format: ExportFormat, mut sink: F) -> Result<(),String> where F: FnMut(Vec<u8>) -> bool {
+        let conn = datasource::get_request_connection(&self.context)
+            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+
+        let names = self.result_names.clone();
+        let formats = self.context.formats;
+
+        let (condition, params) = self.generate_condition();
+        let sql = self.generate_sql(&condition, None);
+
+        let (query, params) = self.prepare_query(&conn, &sql, params, EXPORT_PREFETCH_ROWS)?;
+
+        let iterator = query.fetch_iter(params)
+            .map_err(|err|format!("Can not execute export query: {}", err))?;
+
+        let mut chunk = Vec::with_capacity(EXPORT_CHUNK_SIZE);
+        match format {
+            ExportFormat::NdJson => {},
+            ExportFormat::Json => chunk.push(b'['),
+            ExportFormat::Csv => encoding::write_csv_header(&mut chunk, &names),
+            ExportFormat::Xml => {
+                chunk.extend_from_slice(encoding::XML_DECLARATION.as_bytes());
+                chunk.extend_from_slice(b"<rows>");
+            }
+        }
+
+        for (idx, row) in iterator.enumerate() {
+            let row = row.map_err(|err|format!("Can not fetch row while export: {}", err))?;
+
+            match format {
+                ExportFormat::NdJson => {
+                    encoding::write_row(&mut chunk, ResultFormat::Json, formats, &names, &row.values);
+                    chunk.push(b'\n');
+                },
+                ExportFormat::Json => {
+                    if idx > 0 {
+                        chunk.push(b',');
+                    }
+                    encoding::write_row(&mut chunk, ResultFormat::Json, formats, &names, &row.values);
+                },
+                ExportFormat::Csv => encoding::write_row(&mut chunk, ResultFormat::Csv, formats, &names, &row.values),
+                ExportFormat::Xml => encoding::write_row(&mut chunk, ResultFormat::Xml, formats, &names, &row.values),
+            }
+
+            if chunk.len() >= EXPORT_CHUNK_SIZE {
+                let full = std::mem::replace(&mut chunk, Vec::with_capacity(EXPORT_CHUNK_SIZE));
+                if !sink(full) {
+                    return Ok(());
+                }
+            }
+        }
+
+        match format {
+            ExportFormat::Json => chunk.push(b']'),
+            ExportFormat::Xml => chunk.extend_from_slice(b"</rows>"),
+            ExportFormat::NdJson | ExportFormat::Csv => {}
+        }
+        if !chunk.is_empty() {
+            sink(chunk);
+        }
+
+        Ok(())
+    }
+
+    fn prepare_query<'conn>(self, conn: &'conn oracle::Connection, sql: &str, params: FilterParams, prefetch_rows: usize) -> Result<(oracle::Query<'conn, Vec<ParsedParameter>, DynamicRow>, Vec<ParsedParameter>), String> {
         // println!("sql: {}", &sql);
 
-        let results_provider = Box::new( DynamicResultsProvider { columns: self.columns, column_names: self.column_names } );
-        let params_provider = Box::new( DynamicParamsProvider { columns: self.param_columns });
+        let results_provider = Box::new( DynamicResultsProvider {
+            columns: self.columns, key_indices: self.key_indices.unwrap_or_default() } );
+        let params_provider = Box::new( DynamicParamsProvider { columns: params.columns });
 
-        let stmt = conn.prepare_dynamic(&sql, params_provider)
+        let stmt = conn.prepare_dynamic(sql, params_provider)
             .map_err(|err|format!("Can not prepare statement: {}", err))?;
 
         let query = stmt.query_dynamic(results_provider, prefetch_rows)
             .map_err(|err|format!("Can not create query from statement: {}", err))?;
 
-        Ok((query, self.parsed_params))
+        Ok((query, params.values))
+    }
+
+}
+
+/// where clause with binded parameters, empty if predicate has no conditions
+fn where_clause(predicate: Predicate) -> (String, FilterParams) {
+    let mut params = FilterParams::new();
+
+    if predicate.is_empty() {
+        (String::new(), params)
+    } else {
+        let condition = format!(" WHERE {}", predicate.generate_sql(&mut params));
+        (condition, params)
+    }
+}
+
+/// execute `SELECT COUNT(*)` query
+fn count_rows(conn: &oracle::Connection, sql: &str, params: FilterParams) -> Result<i64, String> {
+    let params_provider = Box::new( DynamicParamsProvider { columns: params.columns });
+
+    let query = conn.prepare_dynamic(sql, params_provider)
+        .and_then(|stmt|stmt.query_one::<i64>())
+        .map_err(|err|format!("Can not prepare count statement: {}", err))?;
+
+    let count = query.fetch_one(params.values)
+        .map_err(|err|format!("Can not count rows: {}", err))?;
+
+    Ok(count.unwrap_or(0))
+}
+
+/// execute query with dynamic columns and fetch all rows, it is used for expanded relations and functions
+pub(super) fn fetch_rows(conn: &oracle::Connection, sql: &str, params: FilterParams, columns: Vec<ColTypeInfo>) -> Result<Vec<Vec<ColumnValue>>, String> {
+    let results_provider = Box::new( DynamicResultsProvider { columns, key_indices: vec![] } );
+    let params_provider = Box::new( DynamicParamsProvider { columns: params.columns });
+
+    let query = conn.prepare_dynamic(sql, params_provider)
+        .and_then(|stmt|stmt.query_dynamic(results_provider, 100))
+        .map_err(|err|format!("Can not prepare query: {}", err))?;
+
+    let rows = query.fetch_list(params.values)
+        .map_err(|err|format!("Can not fetch rows: {}", err))?;
+
+    Ok(rows.into_iter().map(|row|row.values).collect())
+}
+
+/// position of column in selected columns.
+/// column absent in projection is selected, but not present in result (hidden)
+pub(super) fn select_hidden(columns: &mut Vec<ColTypeInfo>, column_names: &mut Vec<String>, column: &mi::ColumnInfo) -> usize {
+    column_names.iter().position(|name|name == &column.name).unwrap_or_else(|| {
+        columns.push(ColTypeInfo::new(column));
+        column_names.push(column.name.to_owned());
+        column_names.len() - 1
+    })
+}
+
+/// selected columns: types, names in sql and names in result
+type SelectedColumns = (Vec<ColTypeInfo>, Vec<String>, Vec<String>);
+
+/// parse `select=col1,col2:alias` projection, check columns with metainfo.
+/// all columns of table except LOB columns are selected if projection is empty
+fn select_columns(table_info: &mi::TableInfo, select: Vec<String>) -> Result<SelectedColumns, String> {
+    if select.is_empty() {
+        let columns = table_info.columns.iter().filter(|c|!c.is_lob()).map(ColTypeInfo::new).collect();
+        let column_names: Vec<String> = table_info.columns.iter().filter(|c|!c.is_lob()).map(|c|c.name.to_string()).collect();
+        let result_names = column_names.clone();
+        return Ok((columns, column_names, result_names));
+    }
+
+    let mut columns = Vec::with_capacity(select.len());
+    let mut column_names = Vec::with_capacity(select.len());
+    let mut result_names: Vec<String> = Vec::with_capacity(select.len());
+
+    for item in select {
+        let mut parts = item.splitn(2, ':');
+        let col_name = parts.next().unwrap_or("").trim();
+        let alias = parts.next().map(|alias|alias.trim()).unwrap_or(col_name);
+
+        let column = table_info.columns
+            .iter()
+            .find(|c|c.name == col_name)
+            .ok_or_else(||format!("Selected column {} not found in table {}", col_name, table_info.name))?;
+
+        if column.is_lob() {
+            return Err(format!("LOB column {} can not be selected, read it by /schemas/{{schema}}/{{table}}/{{pk}}/{}", col_name, col_name));
+        }
+
+        // alias is used as name of XML element, so it can not start with digit
+        if alias.is_empty() || alias.starts_with(|c: char|c.is_ascii_digit()) || !alias.chars().all(|c|c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid name '{}' for selected column {}", alias, col_name));
+        }
+        if result_names.iter().any(|name|name == alias) {
+            return Err(format!("Duplicated name '{}' in selected columns", alias));
+        }
+
+        columns.push(ColTypeInfo::new(column));
+        column_names.push(column.name.to_owned());
+        result_names.push(alias.to_string());
     }
 
+    Ok((columns, column_names, result_names))
+}
+
+/// parse primary key values from url into parameters of where clause
+pub fn parse_pk_params(table_info: &mi::TableInfo, pk_params: Vec<String>, binary: BinaryFormat) -> Result<(Vec<String>, Vec<ColTypeInfo>, Vec<ParsedParameter>), String> {
+    let pk = table_info.primary_key.as_ref().ok_or("Primary key not exists".to_string())?;
+    let param_columns_len = pk_params.len();
+
+    if param_columns_len != pk.column_indices.len() {
+        return Err("Count of columns in primary key does not match with count of parameters in query".to_string())
+    }
+
+    let mut param_column_names = Vec::with_capacity(param_columns_len);
+    let mut param_columns = Vec::with_capacity(param_columns_len);
+    let mut parsed_params = Vec::with_capacity(param_columns_len);
+
+    for (pk_column_index, p) in pk.column_indices.iter().zip(pk_params) {
+        let pk_column = unsafe { table_info.columns.get_unchecked(*pk_column_index) };
+
+        let param_column = ColTypeInfo::new( pk_column );
+        let parsed = ParsedParameter::parse(&param_column, p.to_string(), binary);
+        match parsed {
+            Err(err) => return Err(format!("Can not parse parameter value {} for column {}: {}", p, pk_column.name, err)),
+            Ok(parsed) => {
+                parsed_params.push(parsed);
+                param_columns.push(param_column);
+                param_column_names.push(pk_column.name.to_owned());
+            }
+        }
+    };
+
+    Ok((param_column_names, param_columns, parsed_params))
 }
 
 impl ParsedParameter {
-    fn parse(tp: oracle::SqlType, value: String) -> Result<Self, &'static str> {
-        match tp {
+    /// parse text value of column with binary values in format, string or binary value longer than column is rejected
+    pub fn parse(col: &ColTypeInfo, value: String, binary: BinaryFormat) -> Result<Self, &'static str> {
+        match col.col_type {
             oracle::SqlType::Int16 => {
-                let val: i16 = value.parse().unwrap();
+                let val: i16 = value.parse().map_err(|_|"Invalid 16-bit integer value")?;
                 Ok(ParsedParameter::Int16(val))
             },
             oracle::SqlType::Int32 => {
-                let val: i32 = value.parse().unwrap();
+                let val: i32 = value.parse().map_err(|_|"Invalid 32-bit integer value")?;
                 Ok(ParsedParameter::Int32(val))
             },
             oracle::SqlType::Int64 => {
-                let val: i64 = value.parse().unwrap();
+                let val: i64 = value.parse().map_err(|_|"Invalid 64-bit integer value")?;
                 Ok(ParsedParameter::Int64(val))
             },
+            oracle::SqlType::Float64 => {
+                let val: f64 = value.parse().map_err(|_|"Invalid float value")?;
+                Ok(ParsedParameter::Float64(val))
+            },
+            oracle::SqlType::Decimal => {
+                let val: oracle::SqlDecimal = value.parse().map_err(|_|"Invalid decimal value")?;
+                Ok(ParsedParameter::Decimal(val))
+            },
             oracle::SqlType::Varchar => {
+                if value.len() > col.oci_data_type.capacity() {
+                    return Err("String value is too long for column");
+                }
                 Ok(ParsedParameter::Varchar(value))
             },
-            _ => Err("Not supported type for Primary key")
+            oracle::SqlType::Raw | oracle::SqlType::LongRaw => {
+                let val = binary.parse_value(&value)?;
+                if val.len() > col.oci_data_type.capacity() {
+                    return Err("Binary value is too long for column");
+                }
+                Ok(ParsedParameter::Raw(val))
+            },
+            oracle::SqlType::DateTime => {
+                let val = DateTime::parse_from_rfc3339(&value).map_err(|_|"Invalid RFC 3339 datetime value")?;
+                Ok(ParsedParameter::DateTime(val.with_timezone(&Local)))
+            },
+            oracle::SqlType::Timestamp => {
+                Ok(ParsedParameter::Timestamp(encoding::parse_timestamp(&value)?))
+            },
+            oracle::SqlType::TimestampTz | oracle::SqlType::TimestampLtz => {
+                let val = DateTime::parse_from_rfc3339(&value).map_err(|_|"Invalid RFC 3339 datetime value")?;
+                Ok(ParsedParameter::TimestampTz(val))
+            },
+            oracle::SqlType::IntervalYM => {
+                Ok(ParsedParameter::IntervalYM(encoding::parse_interval_ym(&value)?))
+            },
+            oracle::SqlType::IntervalDS => {
+                Ok(ParsedParameter::IntervalDS(encoding::parse_interval_ds(&value)?))
+            },
+            _ => Err("Not supported type for parameter")
+        }
+    }
+
+    /// parse value of column from json body, json null is NULL value.
+    /// json numbers are parsed as f64, exact decimals with more than 15 digits must be strings
+    pub fn parse_json(col: &ColTypeInfo, value: &serde_json::Value, binary: BinaryFormat) -> Result<Self, &'static str> {
+        use serde_json::Value;
+        match value {
+            Value::Null => Ok(ParsedParameter::Null),
+            Value::String(s) => ParsedParameter::parse(col, s.to_owned(), binary),
+            Value::Number(n) => ParsedParameter::parse(col, n.to_string(), binary),
+            Value::Bool(b) => ParsedParameter::parse(col, (*b as i16).to_string(), binary),
+            _ => Err("Arrays and objects are not supported as column value")
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        match self {
+            Self::Null => true,
+            _ => false
         }
     }
 
@@ -259,29 +774,60 @@ impl ParsedParameter {
             Self::Int64(val) => {
                 val.project_value(p);
             },
+            Self::Float64(val) => {
+                val.project_value(p);
+            },
+            Self::Decimal(val) => {
+                val.project_value(p);
+            },
             Self::Varchar(val) => {
                 val.project_value(p);
             },
+            Self::Raw(val) => {
+                val.project_value(p);
+            },
+            Self::DateTime(val) => {
+                val.project_value(p);
+            },
+            Self::Timestamp(val) => {
+                val.project_value(p);
+            },
+            Self::TimestampTz(val) => {
+                val.project_value(p);
+            },
+            Self::IntervalYM(val) => {
+                val.project_value(p);
+            },
+            Self::IntervalDS(val) => {
+                val.project_value(p);
+            },
+            Self::Null => {
+                p.project_optional::<i16,_>(&None, |_,_| 0);
+            }
         };
     }
 }
 
-impl oracle::ResultsProvider<String> for DynamicResultsProvider {
+impl oracle::ResultsProvider<DynamicRow> for DynamicResultsProvider {
     fn sql_descriptors(&self) -> Vec<oracle::TypeDescriptor> {
         self.columns.iter().map(|c|c.oci_data_type.clone()).collect()
     }
 
-    fn gen_result(&self, rs: oracle::ResultSet) -> String {
-        let results: Vec<String> = self.columns
+    /// values of all selected columns including hidden ordering key, they are skipped by encoder
+    fn gen_result(&self, rs: oracle::ResultSet) -> DynamicRow {
+        let values: Vec<ColumnValue> = self.columns
             .iter()
-            .zip(self.column_names.iter())
             .zip(rs.iter())
-            .map(|((c, name), value)|{
-                let result = value.to_owned().try_to_string(&c.col_type, c.nullable).unwrap_or_else(|err| err.to_string());
-                format!("\"{}\":{}", name, result)
-            }).collect();
+            .map(|(c, value)|ColumnValue::from_result(value, c.col_type))
+            .collect();
 
-        format!("{{ {} }}", results.join(","))
+        // ordering key columns are not nullable
+        let key = self.key_indices
+            .iter()
+            .map(|idx|values[*idx].to_text(encoding::INTERNAL_BINARY).unwrap_or_default())
+            .collect();
+
+        DynamicRow { values, key }
     }
 }
 
@@ -301,3 +847,20 @@ impl oracle::ParamsProvider<Vec<ParsedParameter>> for DynamicParamsProvider {
         }
     }
 }
+
+impl oracle::ParamsProvider<Vec<ParsedParameter>> for DirectedParamsProvider {
+    fn members(&self) -> Vec<oracle::Member> {
+        self.columns.iter()
+            .map(|c| {
+                oracle::Member::with_direction(c.column.oci_data_type, oracle::Identifier::Unnamed, c.direction)
+            })
+            .collect()
+    }
+
+    fn project_values(&self, params: &Vec<ParsedParameter>, projecton: &mut oracle::ParamsProjection) {
+        for (idx,param) in params.iter().enumerate() {
+            let p = unsafe { projecton.get_unchecked_mut(idx) };
+            param.project_value(p);
+        }
+    }
+}
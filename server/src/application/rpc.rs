@@ -0,0 +1,146 @@
+use serde_json::{Map, Value};
+
+use crate::{metainfo as mi, datasource};
+use crate::datasource::RequestContext;
+use crate::application::query::{ColTypeInfo, DirectedParameter, DirectedParamsProvider, ParsedParameter};
+use crate::application::encoding::{self, BinaryFormat, ColumnValue, ResultFormat};
+
+/// Call of stored procedure or function, arguments are binded by name from json body
+pub struct DynamicCall {
+    sql:           String,
+    param_columns: Vec<DirectedParameter>,
+    parsed_params: Vec<ParsedParameter>,
+    // names of OUT and IN OUT arguments and function result in response, with positions of parameters
+    outputs:       Vec<(String, usize)>,
+    context:       RequestContext,
+}
+
+impl DynamicCall {
+    /// BEGIN schema.package.procedure(a => :1, b => :2); END;
+    /// BEGIN :1 := schema.package.function(a => :2); END;
+    pub fn create(schema_name: &str,
+                  schema_info: &mi::SchemaInfo,
+                  package:     Option<&str>,
+                  name:        &str,
+                  values:      Map<String,Value>,
+                  binary:      BinaryFormat
+    ) -> Result<DynamicCall, String> {
+        let procedure = find_overload(schema_info, package, name, &values)?;
+
+        if procedure.unsupported {
+            return Err(format!("Procedure {} has arguments of unsupported types", name));
+        }
+
+        let mut param_columns = Vec::with_capacity(procedure.arguments.len() + 1);
+        let mut parsed_params = Vec::with_capacity(procedure.arguments.len() + 1);
+        let mut outputs = Vec::new();
+        let mut binds = Vec::with_capacity(procedure.arguments.len());
+
+        if let Some(result) = &procedure.result {
+            param_columns.push( DirectedParameter { column: ColTypeInfo::for_argument(result), direction: oracle::Direction::Out });
+            parsed_params.push(ParsedParameter::Null);
+            outputs.push(("result".to_string(), 0));
+        }
+
+        // absent input arguments have default values, output arguments are always binded
+        for arg in &procedure.arguments {
+            let column = ColTypeInfo::for_argument(arg);
+            let parsed = match values.get(&arg.name) {
+                Some(value) => ParsedParameter::parse_json(&column, value, binary)
+                    .map_err(|err|format!("Can not parse value {} for argument {}: {}", value, arg.name, err))?,
+                None if arg.direction.is_output() => ParsedParameter::Null,
+                None => continue
+            };
+
+            let direction = match arg.direction {
+                mi::ArgumentDirection::In => oracle::Direction::In,
+                mi::ArgumentDirection::Out => oracle::Direction::Out,
+                mi::ArgumentDirection::InOut => oracle::Direction::InOut,
+            };
+            if arg.direction.is_output() {
+                outputs.push((arg.name.to_owned(), parsed_params.len()));
+            }
+
+            param_columns.push( DirectedParameter { column, direction });
+            parsed_params.push(parsed);
+            binds.push(format!("{} => :{}", arg.name, parsed_params.len()));
+        }
+
+        let call = match package {
+            Some(package) => format!("{}.{}.{}({})", schema_name, package, name, binds.join(", ")),
+            None => format!("{}.{}({})", schema_name, name, binds.join(", "))
+        };
+
+        let sql = match procedure.result {
+            Some(_) => format!("BEGIN :1 := {}; END;", call),
+            None => format!("BEGIN {}; END;", call)
+        };
+
+        Ok( DynamicCall { sql, param_columns, parsed_params, outputs, context: RequestContext::default() } )
+    }
+
+    /// tracing attributes, timeout and cancellation of oracle session
+    pub fn with_context(mut self, context: RequestContext) -> DynamicCall {
+        self.context = context;
+        self
+    }
+
+    /// execute a call and commit transaction, it is rolled back on error.
+    /// result is json object with OUT arguments, result of function is in `result` member
+    pub fn execute(self) -> Result<Vec<u8>,String> {
+        let DynamicCall { sql, param_columns, parsed_params, outputs, context } = self;
+
+        let conn = datasource::get_request_connection(&context)
+            .map_err(|err|format!("Can not connect to oracle: {}", err))?;
+        let tx = conn.begin();
+
+        let values = {
+            let params_provider = Box::new( DirectedParamsProvider { columns: param_columns.clone() });
+            let stmt = tx.prepare_dynamic(&sql, params_provider)
+                .map_err(|err|format!("Can not prepare call: {}", err))?;
+
+            let out = stmt.execute_out(parsed_params)
+                .map_err(|err|format!("Can not execute procedure: {}", err))?;
+
+            outputs
+                .iter()
+                .map(|(_, idx)|ColumnValue::from_result(&out.value(*idx), param_columns[*idx].column.col_type))
+                .collect::<Vec<ColumnValue>>()
+        };
+
+        tx.commit()
+            .map_err(|err|format!("Can not commit transaction: {}", err))?;
+
+        let names: Vec<String> = outputs.into_iter().map(|(name, _)|name).collect();
+        let mut body = Vec::new();
+        encoding::write_row(&mut body, ResultFormat::Json, context.formats, &names, &values);
+        Ok(body)
+    }
+}
+
+/// choose overload of procedure by names of arguments in body.
+/// all arguments in body must be input arguments, input arguments without default must be in body
+fn find_overload<'a>(schema_info: &'a mi::SchemaInfo, package: Option<&'a str>, name: &'a str, values: &Map<String,Value>) -> Result<&'a mi::ProcedureInfo, String> {
+    let overloads: Vec<&mi::ProcedureInfo> = schema_info.find_procedures(package, name).collect();
+
+    let check = |procedure: &mi::ProcedureInfo| -> Result<(), String> {
+        if let Some(key) = values.keys().find(|key|!procedure.arguments.iter().any(|a|a.name == **key && a.direction.is_input())) {
+            return Err(format!("Procedure {} has no input argument {}", name, key));
+        }
+        if let Some(arg) = procedure.arguments.iter().find(|a|a.direction.is_input() && !a.defaulted && !values.contains_key(&a.name)) {
+            return Err(format!("Argument {} of procedure {} must have value", arg.name, name));
+        }
+        Ok(())
+    };
+
+    if overloads.len() == 1 {
+        return check(overloads[0]).map(|_|overloads[0]);
+    }
+
+    let mut matched: Vec<&mi::ProcedureInfo> = overloads.into_iter().filter(|p|check(p).is_ok()).collect();
+    match matched.len() {
+        0 => Err(format!("No overload of procedure {} matches with arguments", name)),
+        1 => Ok(matched.remove(0)),
+        _ => Err(format!("Several overloads of procedure {} match with arguments", name))
+    }
+}
@@ -11,13 +11,53 @@ pub struct Config {
     pub connection: ConnectionConfig,
     pub excludes:   Excludes,
     pub http:       HTTP,
+    pub timeouts:   Option<Timeouts>,
+    pub encoding:   Option<Encoding>,
 }
 
 #[derive(Deserialize, Debug, PartialEq)]
 pub struct ConnectionConfig {
     pub url:  String,
     pub user: String,
-    pub pw:   String
+    pub pw:   String,
+    pub pool: Option<PoolConfig>
+}
+
+/// session pool, absent attributes have defaults of oracle::SessionPoolBuilder.
+/// get_mode is wait, nowait or timedwait (with wait_timeout in milliseconds), timeouts are in seconds
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct PoolConfig {
+    pub min:          Option<u32>,
+    pub max:          Option<u32>,
+    pub increment:    Option<u32>,
+    pub get_mode:     Option<String>,
+    pub wait_timeout: Option<u32>,
+    pub idle_timeout: Option<u32>,
+    pub max_lifetime: Option<u32>,
+    pub stmt_cache:   Option<u32>,
+}
+
+/// maximal execution time of requests in milliseconds, by route pattern (e.g. /api/schemas/{schema}/{table}/).
+/// absent or 0 timeout is unlimited
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Timeouts {
+    pub default: Option<u32>,
+    #[serde(rename = "route", default)]
+    pub routes:  Vec<RouteTimeout>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct RouteTimeout {
+    pub path:    String,
+    pub timeout: u32,
+}
+
+/// representation of values in results, decimal is number (default) or string in JSON,
+/// binary is hex (default) or base64
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Encoding {
+    pub decimal: Option<String>,
+    pub binary:  Option<String>,
 }
 
 #[derive(Deserialize, Debug, PartialEq)]
@@ -1,23 +1,94 @@
 use std::env;
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 use lazy_static::lazy_static;
+use slog::warn;
 
-use crate::config::ConnectionConfig;
+use crate::config::{ConnectionConfig, PoolConfig};
+use crate::application::ValueFormats;
 use oracle;
 
 pub struct Datasource {
     pool: oracle::SessionPool,
+    log:  slog::Logger,
+}
+
+/// connect longer than threshold in microseconds is counted as wait for released session,
+/// getting of idle session takes much less time. creation of new session by pool is counted too
+const WAIT_THRESHOLD_US: u64 = 10_000;
+
+/// errors of exhausted pool: ORA-24418 in nowait mode, ORA-24457 on timeout in timedwait mode
+const POOL_EXHAUSTED_ERRORS: [i32; 2] = [24418, 24457];
+
+/// Counters of connects to pool, latency in microseconds
+struct ConnectCounters {
+    connects:      AtomicU64,
+    errors:        AtomicU64,
+    waits:         AtomicU64,
+    total_latency: AtomicU64,
+    max_latency:   AtomicU64,
+}
+
+/// Tracing attributes of oracle session, they are visible in V$SESSION
+#[derive(Clone, Default)]
+pub struct SessionTrace {
+    pub client_identifier: String,
+    pub module:            String,
+    pub action:            String,
+    pub client_info:       String,
+}
+
+/// Oracle sessions of request: tracing attributes, timeout of calls in milliseconds (0 is unlimited) and cancellation,
+/// and formats of values in results of request
+#[derive(Clone, Default)]
+pub struct RequestContext {
+    pub trace:        SessionTrace,
+    pub timeout:      u32,
+    pub cancellation: Cancellation,
+    pub formats:      ValueFormats,
+}
+
+/// Cancellation of calls in all connections of request, connection of cancelled request is not got
+#[derive(Clone, Default)]
+pub struct Cancellation {
+    state: Arc<Mutex<CancelState>>,
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: bool,
+    handles:   Vec<oracle::CancelHandle>,
+}
+
+/// Guard of request, it cancels calls of request on drop
+pub struct CancelGuard {
+    cancellation: Cancellation,
+}
+
+/// Statistics of pool and connects
+pub struct DatasourceStats {
+    pub pool:           oracle::PoolStats,
+    pub connects:       u64,
+    pub errors:         u64,
+    pub waits:          u64,
+    pub avg_latency_us: u64,
+    pub max_latency_us: u64,
 }
 
 type DatasourceHandler = RwLock<Option<Datasource>>;
 
 lazy_static! {
   static ref DATASOURCE: DatasourceHandler = RwLock::new(None);
+  static ref COUNTERS: ConnectCounters = ConnectCounters {
+      connects: AtomicU64::new(0), errors: AtomicU64::new(0), waits: AtomicU64::new(0),
+      total_latency: AtomicU64::new(0), max_latency: AtomicU64::new(0)
+  };
 }
 
 impl Datasource {
-    fn new(config: &ConnectionConfig) -> oracle::OracleResult<Datasource> {
+    fn new(config: &ConnectionConfig, log: slog::Logger) -> oracle::OracleResult<Datasource> {
         let url = &config.url;
         let user = &config.user;
         let mut pw = config.pw.clone();
@@ -27,17 +98,53 @@ impl Datasource {
             pw = env::var(key).unwrap_or(pw);
         };
 
-        let pool = oracle::create_pool(url, user, &pw)?;
-        Ok(Datasource{pool})
+        let mut builder = oracle::SessionPool::builder(url, user, &pw);
+        if let Some(pool_config) = &config.pool {
+            builder = configure_pool(builder, pool_config)?;
+        }
+
+        let pool = builder.build()?;
+        Ok(Datasource{pool, log})
+    }
+}
+
+fn configure_pool(mut builder: oracle::SessionPoolBuilder, config: &PoolConfig) -> oracle::OracleResult<oracle::SessionPoolBuilder> {
+    if let Some(min) = config.min {
+        builder = builder.min(min);
+    }
+    if let Some(max) = config.max {
+        builder = builder.max(max);
+    }
+    if let Some(increment) = config.increment {
+        builder = builder.increment(increment);
+    }
+    if let Some(get_mode) = &config.get_mode {
+        let get_mode = match get_mode.as_str() {
+            "wait" => oracle::GetMode::Wait,
+            "nowait" => oracle::GetMode::NoWait,
+            "timedwait" => oracle::GetMode::TimedWait(config.wait_timeout.unwrap_or(5000)),
+            _ => return Err(oracle::OracleError::new(format!("Invalid get_mode '{}' of pool, must be wait, nowait or timedwait", get_mode), "datasource::configure_pool"))
+        };
+        builder = builder.get_mode(get_mode);
     }
+    if let Some(seconds) = config.idle_timeout {
+        builder = builder.idle_timeout(seconds);
+    }
+    if let Some(seconds) = config.max_lifetime {
+        builder = builder.max_lifetime(seconds);
+    }
+    if let Some(size) = config.stmt_cache {
+        builder = builder.stmt_cache_size(size);
+    }
+    Ok(builder)
 }
 
-pub fn create(config: &ConnectionConfig) -> Result<(), String> {
+pub fn create(config: &ConnectionConfig, log: slog::Logger) -> Result<(), String> {
     let mut ds = (*DATASOURCE).write()
         .map_err(|_err| format!("Can not get lock for datasource creation"))?;
 
     if let None = *ds {
-        let datasource = Datasource::new(config)
+        let datasource = Datasource::new(config, log)
             .map_err(|err| format!("Can not create connection pool: {}", err))?;
         *ds = Some(datasource);
     };
@@ -49,5 +156,89 @@ pub fn get_connection() -> oracle::OracleResult<oracle::Connection> {
     let ds = (*DATASOURCE).read().unwrap();
     let cc = ds.as_ref().unwrap();
     // oracle::connect(&cc.url, &cc.user, &cc.pw)
-    cc.pool.connect()
+    let started = Instant::now();
+    let conn = cc.pool.connect();
+    let latency = started.elapsed().as_micros() as u64;
+
+    COUNTERS.connects.fetch_add(1, Ordering::Relaxed);
+    COUNTERS.total_latency.fetch_add(latency, Ordering::Relaxed);
+    COUNTERS.max_latency.fetch_max(latency, Ordering::Relaxed);
+    if conn.is_err() {
+        COUNTERS.errors.fetch_add(1, Ordering::Relaxed);
+    }
+    let exhausted = matches!(&conn, Err(err) if POOL_EXHAUSTED_ERRORS.contains(&err.errcode));
+    if latency >= WAIT_THRESHOLD_US || exhausted {
+        COUNTERS.waits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    conn
+}
+
+/// get connection for request: set tracing attributes and call timeout, they replace settings of previous
+/// user of pooled session. connection is registered for cancellation of request
+pub fn get_request_connection(context: &RequestContext) -> oracle::OracleResult<oracle::Connection> {
+    let conn = get_connection()?;
+    let trace = &context.trace;
+    conn.set_client_identifier(&trace.client_identifier)?;
+    conn.set_module(&trace.module)?;
+    conn.set_action(&trace.action)?;
+    conn.set_client_info(&trace.client_info)?;
+    conn.set_call_timeout(context.timeout)?;
+    context.cancellation.register(&conn)?;
+    Ok(conn)
+}
+
+impl Cancellation {
+    fn register(&self, conn: &oracle::Connection) -> oracle::OracleResult<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.cancelled {
+            return Err(oracle::OracleError::new("Request is cancelled".to_string(), "datasource::register"));
+        }
+        state.handles.push(conn.cancel_handle()?);
+        Ok(())
+    }
+
+    /// cancel current calls of request, released connections are skipped
+    pub fn cancel(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.cancelled = true;
+        let ds = (*DATASOURCE).read().unwrap();
+        for handle in state.handles.drain(..) {
+            // handles are registered by connections of pool, so datasource exists
+            if let (Err(err), Some(ds)) = (handle.cancel(), ds.as_ref()) {
+                warn!(ds.log, "Can not cancel call of request: {}", err);
+            }
+        }
+    }
+
+    pub fn guard(&self) -> CancelGuard {
+        CancelGuard { cancellation: self.clone() }
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        self.cancellation.cancel();
+    }
+}
+
+pub fn stats() -> Result<DatasourceStats, String> {
+    let ds = (*DATASOURCE).read()
+        .map_err(|_err| format!("Can not get lock for datasource statistics"))?;
+    let cc = ds.as_ref().ok_or_else(||"Datasource is not created".to_string())?;
+
+    let pool = cc.pool.stats()
+        .map_err(|err| format!("Can not get statistics of connection pool: {}", err))?;
+
+    let connects = COUNTERS.connects.load(Ordering::Relaxed);
+    let avg_latency_us = if connects > 0 { COUNTERS.total_latency.load(Ordering::Relaxed) / connects } else { 0 };
+
+    Ok( DatasourceStats {
+        pool,
+        connects,
+        errors: COUNTERS.errors.load(Ordering::Relaxed),
+        waits: COUNTERS.waits.load(Ordering::Relaxed),
+        avg_latency_us,
+        max_latency_us: COUNTERS.max_latency.load(Ordering::Relaxed)
+    })
 }
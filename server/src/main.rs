@@ -21,6 +21,8 @@ mod security;
 //       /schemas   metadata-catalog
 //   /api           web applications api
 //       /schemas   tables / views / procedures
+//   /export        streaming export of whole tables
+//       /schemas   tables / views
 //   /              static files / web-server
 
 #[actix_web::main]
@@ -35,7 +37,7 @@ async fn main() -> std::io::Result<()> {
     let builder = setup::ssl(&http);
     let identityService = setup::identity(&http);
 
-    datasource::create(&conf.connection)
+    datasource::create(&conf.connection, log.clone())
         .map_err(|e|Error::new(ErrorKind::Other, e))?;
 
     let application = application::ApplicationState::load(&conf)?;
@@ -50,7 +52,10 @@ async fn main() -> std::io::Result<()> {
             .wrap(identityService.clone())
 
             .service(application::management_scope())
+            .service(application::rpc_scope())
+            .service(application::write_scope())
             .service(application::api_scope())
+            .service(application::export_scope())
             .service( application::base_scope())
     })
         .keep_alive(75)
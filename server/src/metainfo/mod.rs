@@ -32,17 +32,21 @@ impl MetaInfo {
         let mut columns_count = 0;
         let mut pks_count = 0;
         let mut indexes_count = 0;
+        let mut fks_count = 0;
+        let mut procedures_count = 0;
 
         for schema in schemas.iter() {
             for table in schema.tables.iter() {
                 tables_count += 1;
                 columns_count += table.columns.len();
                 indexes_count += table.indexes.len();
+                fks_count += table.foreign_keys.len();
 
                 if table.primary_key.is_some() {
                     pks_count += 1;
                 }
             }
+            procedures_count += schema.procedures.len();
             schemas_count += 1;
         }
 
@@ -50,6 +54,8 @@ impl MetaInfo {
         println!("TOTAL:   {} schemas with {} tables & views and {} columns", schemas_count,  tables_count, columns_count);
         println!("         {} tables with primary keys", pks_count);
         println!("         {} indexes found", indexes_count);
+        println!("         {} foreign keys found", fks_count);
+        println!("         {} procedures and functions found", procedures_count);
 
         let end = chrono::offset::Local::now();
         let duration = end - start;
@@ -71,6 +77,9 @@ impl MetaInfo {
         let mut schemas = MetaInfo::load_tables(&conn, &joined_excludes)?;
         MetaInfo::load_primary_keys(&conn, &joined_excludes, &mut schemas)?;
         MetaInfo::load_indexes(&conn, &joined_excludes, &mut schemas)?;
+        MetaInfo::load_foreign_keys(&conn, &joined_excludes, &mut schemas)?;
+        MetaInfo::load_comments(&conn, &joined_excludes, &mut schemas)?;
+        MetaInfo::load_procedures(&conn, &joined_excludes, &mut schemas)?;
 
         Ok(schemas)
     }
@@ -123,11 +132,13 @@ impl MetaInfo {
                 // construct column info and collect it to vector of columns
                 let columns = columns.map(|c|ColumnInfo::try_from(c)).filter_map(|c|c.ok()).collect();
 
-                let table = TableInfo { name, is_view, temporary, num_rows, columns, primary_key: None, indexes: Vec::new() };
+                let table = TableInfo {
+                    name, is_view, temporary, num_rows, comment: None, columns,
+                    primary_key: None, indexes: Vec::new(), foreign_keys: Vec::new() };
                 tables.insert(table);
             }
 
-            let schema = SchemaInfo { name: schema.to_lowercase(), tables};
+            let schema = SchemaInfo { name: schema.to_lowercase(), tables, procedures: Vec::new() };
 
             result.insert(schema);
         };
@@ -219,4 +230,141 @@ impl MetaInfo {
         Ok(())
     }
 
+    fn load_foreign_keys(conn: &oracle::Connection, excludes: &str, schemas: &mut HashSet<SchemaInfo>) -> oracle::OracleResult<()> {
+        let fk_iterator = fetch_foreign_keys(conn, excludes)?;
+
+        // group foreign keys by schema
+        let grouped_keys = fk_iterator
+            .filter_map(|r|r.ok())
+            .group_by(|t| t.owner.clone() );
+
+        for (schema, keys) in grouped_keys.into_iter() {
+            let schema = schemas.get(schema.to_lowercase().as_str());
+
+            if let Some(schema) = schema {
+                // group keys by table name and constraint name
+                let grouped_keys = keys
+                    .group_by(|t| (t.table_name.clone(),t.constraint_name.clone()) );
+
+                for ((table_name, name), key_columns) in grouped_keys.into_iter() {
+                    let table_info = schema.tables.get(table_name.to_lowercase().as_str());
+                    if let Some(table_info) = table_info {
+                        let key_columns: Vec<OraTableForeignKeyColumn> = key_columns.collect();
+
+                        let column_indices: Vec<usize> = key_columns
+                            .iter()
+                            .map(|c|{
+                                let column_name = c.column_name.to_lowercase();
+                                table_info.columns.iter().position(|c|c.name == column_name)
+                            })
+                            .filter_map(|p|p)
+                            .collect();
+
+                        // all columns of foreign key must be supported
+                        if column_indices.len() == key_columns.len() && column_indices.len() > 0 {
+                            let first = &key_columns[0];
+                            let fk = ForeignKey {
+                                name,
+                                column_indices,
+                                ref_schema: first.r_owner.to_lowercase(),
+                                ref_table: first.r_table_name.to_lowercase(),
+                                ref_columns: key_columns.iter().map(|c|c.r_column_name.to_lowercase()).collect(),
+                                delete_rule: DeleteRule::from(first.delete_rule.as_str())
+                            };
+                            table_info.push_foreign_key(fk);
+                        }
+                    } // table info found
+                }
+            } // schema found
+        };
+
+        Ok(())
+    }
+
+    fn load_comments(conn: &oracle::Connection, excludes: &str, schemas: &mut HashSet<SchemaInfo>) -> oracle::OracleResult<()> {
+        let comments_iterator = fetch_comments(conn, excludes)?;
+
+        // group comments by schema
+        let grouped_comments = comments_iterator
+            .filter_map(|r|r.ok())
+            .group_by(|t| t.owner.clone() );
+
+        for (schema, comments) in grouped_comments.into_iter() {
+            let schema = schemas.get(schema.to_lowercase().as_str());
+
+            if let Some(schema) = schema {
+                // group comments by table name
+                let grouped_comments = comments
+                    .group_by(|t| t.table_name.clone() );
+
+                for (table_name, comments) in grouped_comments.into_iter() {
+                    let table_info = schema.tables.get(table_name.to_lowercase().as_str());
+                    if let Some(table_info) = table_info {
+                        for c in comments {
+                            // comment of table has empty column name
+                            if c.column_name.is_empty() {
+                                table_info.set_comment(c.comments);
+                            } else {
+                                let column_name = c.column_name.to_lowercase();
+                                let column_index = table_info.columns.iter().position(|c|c.name == column_name);
+                                if let Some(column_index) = column_index {
+                                    table_info.set_column_comment(column_index, c.comments);
+                                }
+                            }
+                        }
+                    } // table info found
+                }
+            } // schema found
+        };
+
+        Ok(())
+    }
+
+    fn load_procedures(conn: &oracle::Connection, excludes: &str, schemas: &mut HashSet<SchemaInfo>) -> oracle::OracleResult<()> {
+        let args_iterator = fetch_procedures(conn, excludes)?;
+
+        // group arguments by schema
+        let grouped_args = args_iterator
+            .filter_map(|r|r.ok())
+            .group_by(|t| t.owner.clone() );
+
+        for (schema, args) in grouped_args.into_iter() {
+            let schema = schemas.get(schema.to_lowercase().as_str());
+
+            if let Some(schema) = schema {
+                // group arguments by procedure, overloads have different subprogram id
+                let grouped_args = args
+                    .group_by(|t| (t.package_name.clone(), t.procedure_name.clone(), t.subprogram_id) );
+
+                for ((package_name, procedure_name, _), args) in grouped_args.into_iter() {
+                    let args: Vec<OraProcedureArgument> = args.collect();
+
+                    let package = if package_name.is_empty() { None } else { Some(package_name.to_lowercase()) };
+                    let overload = args.first().map(|a|a.overload.clone()).filter(|o|!o.is_empty());
+
+                    let mut arguments = Vec::with_capacity(args.len());
+                    let mut result = None;
+                    let mut unsupported = false;
+
+                    // procedure without arguments has row with empty data type
+                    for arg in args.iter().filter(|a|!a.data_type.is_empty()) {
+                        match ArgumentInfo::try_from(arg) {
+                            // return value of function has position 0 and has no name
+                            Ok(info) if arg.position == 0 => result = Some(info),
+                            Ok(info) => arguments.push(info),
+                            Err(_) => unsupported = true
+                        }
+                    }
+
+                    let procedure = ProcedureInfo {
+                        package, name: procedure_name.to_lowercase(), overload, arguments, result, unsupported
+                    };
+                    schema.push_procedure(procedure);
+                }
+            } // schema found
+        };
+
+        Ok(())
+    }
+
 }
@@ -54,6 +54,54 @@ pub struct OraTableIndexColumn {
 
 pub type IndexColumnsIterator<'iter, 'conn> = QueryIterator<'iter, 'conn, (), OraTableIndexColumn>;
 
+#[derive(SQLResults)]
+pub struct OraTableForeignKeyColumn {
+    pub owner:           String,
+    pub table_name:      String,
+    pub constraint_name: String,
+    pub column_name:     String,
+    pub r_owner:         String,
+    pub r_table_name:    String,
+    pub r_column_name:   String,
+    #[col_size=10]
+    pub delete_rule:     String
+}
+
+pub type ForeignKeyColumnsIterator<'iter, 'conn> = QueryIterator<'iter, 'conn, (), OraTableForeignKeyColumn>;
+
+#[derive(SQLResults)]
+pub struct OraComment {
+    pub owner:       String,
+    pub table_name:  String,
+    pub column_name: String,
+    #[col_size=4000]
+    pub comments:    String
+}
+
+pub type CommentsIterator<'iter, 'conn> = QueryIterator<'iter, 'conn, (), OraComment>;
+
+#[derive(SQLResults)]
+pub struct OraProcedureArgument {
+    pub owner:          String,
+    pub package_name:   String,
+    pub procedure_name: String,
+    pub subprogram_id:  i32,
+    #[col_size=40]
+    pub overload:       String,
+    pub argument_name:  String,
+    pub position:       u16,
+    pub data_type:      String,
+    #[col_size=6]
+    pub in_out:         String,
+    #[col_size=2]
+    pub defaulted:      String,
+    pub data_length:    u16,
+    pub data_precision: u16,
+    pub data_scale:     u16
+}
+
+pub type ProcedureArgumentsIterator<'iter, 'conn> = QueryIterator<'iter, 'conn, (), OraProcedureArgument>;
+
 pub fn fetch_tables<'iter, 'conn: 'iter>(conn: &'conn oracle::Connection, excludes: &str) -> oracle::OracleResult<TablesIterator<'iter, 'conn>> {
     let sql = format!(
         "SELECT OWNER, TABLE_NAME, TABLE_TYPE, NUM_ROWS, TEMPORARY FROM (
@@ -109,3 +157,56 @@ pub fn fetch_indexes<'iter, 'conn: 'iter>(conn: &'conn oracle::Connection, exclu
     let query = conn.prepare(&sql)?.query_many(1000)?;
     query.fetch_iter(())
 }
+
+pub fn fetch_foreign_keys<'iter, 'conn: 'iter>(conn: &'conn oracle::Connection, excludes: &str) -> oracle::OracleResult<ForeignKeyColumnsIterator<'iter, 'conn>> {
+    // columns of foreign key and referenced key are joined by position in constraint
+    let sql = format!(
+        "SELECT C.OWNER, C.TABLE_NAME, C.CONSTRAINT_NAME, CC.COLUMN_NAME, R.OWNER, R.TABLE_NAME, RC.COLUMN_NAME, C.DELETE_RULE \
+        FROM SYS.ALL_CONSTRAINTS C \
+        JOIN SYS.ALL_CONS_COLUMNS CC ON C.OWNER = CC.OWNER AND C.TABLE_NAME = CC.TABLE_NAME AND C.CONSTRAINT_NAME = CC.CONSTRAINT_NAME
+        JOIN SYS.ALL_CONSTRAINTS R ON C.R_OWNER = R.OWNER AND C.R_CONSTRAINT_NAME = R.CONSTRAINT_NAME
+        JOIN SYS.ALL_CONS_COLUMNS RC ON R.OWNER = RC.OWNER AND R.TABLE_NAME = RC.TABLE_NAME AND R.CONSTRAINT_NAME = RC.CONSTRAINT_NAME AND CC.POSITION = RC.POSITION
+        WHERE C.OWNER NOT IN ( {} ) AND C.CONSTRAINT_TYPE = 'R' AND C.STATUS = 'ENABLED'
+        ORDER BY C.OWNER, C.TABLE_NAME, C.CONSTRAINT_NAME, CC.POSITION"
+        ,excludes
+    );
+
+    let query = conn.prepare(&sql)?.query_many(1000)?;
+    query.fetch_iter(())
+}
+
+/// comments of tables (with empty column name) and columns
+pub fn fetch_comments<'iter, 'conn: 'iter>(conn: &'conn oracle::Connection, excludes: &str) -> oracle::OracleResult<CommentsIterator<'iter, 'conn>> {
+    let sql = format!(
+        "SELECT OWNER, TABLE_NAME, COLUMN_NAME, COMMENTS FROM (
+        SELECT OWNER, TABLE_NAME, NULL AS COLUMN_NAME, COMMENTS
+        FROM SYS.ALL_TAB_COMMENTS
+        UNION ALL
+        SELECT OWNER, TABLE_NAME, COLUMN_NAME, COMMENTS
+        FROM SYS.ALL_COL_COMMENTS
+        ) WHERE OWNER NOT IN ( {} ) AND COMMENTS IS NOT NULL
+        ORDER BY OWNER, TABLE_NAME"
+        ,excludes
+    );
+
+    let query = conn.prepare(&sql)?.query_many(1000)?;
+    query.fetch_iter(())
+}
+
+/// standalone procedures and functions, subprograms of packages with their arguments.
+/// procedure without arguments has one row with empty data type
+pub fn fetch_procedures<'iter, 'conn: 'iter>(conn: &'conn oracle::Connection, excludes: &str) -> oracle::OracleResult<ProcedureArgumentsIterator<'iter, 'conn>> {
+    let sql = format!(
+        "SELECT P.OWNER, CASE WHEN P.OBJECT_TYPE = 'PACKAGE' THEN P.OBJECT_NAME END, NVL(P.PROCEDURE_NAME, P.OBJECT_NAME), \
+        P.SUBPROGRAM_ID, P.OVERLOAD, A.ARGUMENT_NAME, A.POSITION, A.DATA_TYPE, A.IN_OUT, A.DEFAULTED, \
+        A.DATA_LENGTH, A.DATA_PRECISION, A.DATA_SCALE \
+        FROM SYS.ALL_PROCEDURES P \
+        LEFT JOIN SYS.ALL_ARGUMENTS A ON P.OBJECT_ID = A.OBJECT_ID AND P.SUBPROGRAM_ID = A.SUBPROGRAM_ID AND A.DATA_LEVEL = 0
+        WHERE P.OWNER NOT IN ( {} ) AND (P.OBJECT_TYPE IN ('PROCEDURE', 'FUNCTION') OR P.OBJECT_TYPE = 'PACKAGE' AND P.PROCEDURE_NAME IS NOT NULL)
+        ORDER BY P.OWNER, P.OBJECT_NAME, P.SUBPROGRAM_ID, A.SEQUENCE"
+        ,excludes
+    );
+
+    let query = conn.prepare(&sql)?.query_many(1000)?;
+    query.fetch_iter(())
+}
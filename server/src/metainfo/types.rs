@@ -12,8 +12,28 @@ pub struct MetaInfo {
 
 #[derive(Debug, Eq)]
 pub struct SchemaInfo {
-    pub name:    String,
-    pub tables:  HashSet<TableInfo>,
+    pub name:       String,
+    pub tables:     HashSet<TableInfo>,
+    pub procedures: Vec<ProcedureInfo>,
+}
+
+impl SchemaInfo {
+    pub(crate) fn push_procedure(&self, procedure: ProcedureInfo) {
+        self.get_procedures_as_mutable().push(procedure);
+    }
+
+    /// overloads of standalone procedure (package is None) or subprogram of package
+    pub fn find_procedures<'a>(&'a self, package: Option<&'a str>, name: &'a str) -> impl Iterator<Item = &'a ProcedureInfo> {
+        self.procedures.iter().filter(move |p|p.package.as_deref() == package && p.name == name)
+    }
+
+    // hashset don't have get_mut member, but we change only procedures vector, remaining name unchanged
+    fn get_procedures_as_mutable(&self) -> &mut Vec<ProcedureInfo> {
+        let reference = &(self.procedures);
+        let cont_pointer = reference as *const Vec<ProcedureInfo>;
+        let mut_pointer = cont_pointer as *mut Vec<ProcedureInfo>;
+        unsafe { &mut *mut_pointer }
+    }
 }
 
 impl Hash for SchemaInfo {
@@ -38,13 +58,29 @@ pub struct TableInfo {
     pub is_view:     bool,
     pub temporary:   bool,
     pub num_rows:    i32,
+    pub comment:     Option<String>,
     pub columns:     Vec<ColumnInfo>,
-    pub primary_key: Option<PrimaryKey>,
-    pub indexes:     Vec<TableIndex>
+    pub primary_key:  Option<PrimaryKey>,
+    pub indexes:      Vec<TableIndex>,
+    pub foreign_keys: Vec<ForeignKey>
 }
 
 impl TableInfo {
 
+    /// column indices of primary key or of first unique index with not nullable columns.
+    /// it is the ordering key for keyset pagination
+    pub fn ordering_key(&self) -> Option<Vec<usize>> {
+        if let Some(pk) = &self.primary_key {
+            return Some(pk.column_indices.clone());
+        }
+
+        self.indexes
+            .iter()
+            .filter(|index|index.unique)
+            .find(|index|index.columns.iter().all(|c|!self.columns[c.column_index].nullable))
+            .map(|index|index.columns.iter().map(|c|c.column_index).collect())
+    }
+
     pub(crate) fn set_primary_key(&self, pk: PrimaryKey) {
         self.get_primary_key_as_mutable().replace(pk);
     }
@@ -53,6 +89,18 @@ impl TableInfo {
         self.get_indexes_as_mutable().push(index);
     }
 
+    pub(crate) fn push_foreign_key(&self, fk: ForeignKey) {
+        self.get_foreign_keys_as_mutable().push(fk);
+    }
+
+    pub(crate) fn set_comment(&self, comment: String) {
+        self.get_comment_as_mutable().replace(comment);
+    }
+
+    pub(crate) fn set_column_comment(&self, column_index: usize, comment: String) {
+        self.get_columns_as_mutable()[column_index].comment.replace(comment);
+    }
+
     // hashset don't have get_mut member, but we change only primary key, remaining name unchanged
     fn get_primary_key_as_mutable(&self) -> &mut Option<PrimaryKey> {
         let reference = &(self.primary_key);
@@ -69,6 +117,46 @@ impl TableInfo {
         unsafe { &mut *mut_pointer }
     }
 
+    // hashset don't have get_mut member, but we change only comment, remaining name unchanged
+    fn get_comment_as_mutable(&self) -> &mut Option<String> {
+        let reference = &(self.comment);
+        let cont_pointer = reference as *const Option<String>;
+        let mut_pointer = cont_pointer as *mut Option<String>;
+        unsafe { &mut *mut_pointer }
+    }
+
+    // hashset don't have get_mut member, but we change only comments of columns, remaining name unchanged
+    fn get_columns_as_mutable(&self) -> &mut Vec<ColumnInfo> {
+        let reference = &(self.columns);
+        let cont_pointer = reference as *const Vec<ColumnInfo>;
+        let mut_pointer = cont_pointer as *mut Vec<ColumnInfo>;
+        unsafe { &mut *mut_pointer }
+    }
+
+    // hashset don't have get_mut member, but we change only foreign keys vector, remaining name unchanged
+    fn get_foreign_keys_as_mutable(&self) -> &mut Vec<ForeignKey> {
+        let reference = &(self.foreign_keys);
+        let cont_pointer = reference as *const Vec<ForeignKey>;
+        let mut_pointer = cont_pointer as *mut Vec<ForeignKey>;
+        unsafe { &mut *mut_pointer }
+    }
+
+}
+
+#[cfg(test)]
+impl TableInfo {
+    /// table for unit tests with columns (name, type, nullable), without keys, indexes and foreign keys
+    pub fn for_test(name: &str, columns: &[(&str, SqlType, bool)]) -> TableInfo {
+        let columns = columns
+            .iter()
+            .map(|(name, col_type, nullable)| ColumnInfo {
+                name: name.to_string(), col_type: *col_type, oci_data_type: (*col_type).into(),
+                col_type_name: "", nullable: *nullable, comment: None })
+            .collect();
+
+        TableInfo { name: name.to_string(), is_view: false, temporary: false, num_rows: 0, comment: None,
+                    columns, primary_key: None, indexes: vec![], foreign_keys: vec![] }
+    }
 }
 
 impl Hash for TableInfo {
@@ -95,7 +183,15 @@ pub struct ColumnInfo {
     pub col_type:       SqlType,
     pub oci_data_type:  oracle::TypeDescriptor,
     pub col_type_name:  &'static str, // type name in typescript
-    pub nullable:       bool
+    pub nullable:       bool,
+    pub comment:        Option<String>
+}
+
+impl ColumnInfo {
+    /// CLOB, NCLOB, BLOB or BFILE column
+    pub fn is_lob(&self) -> bool {
+        matches!(self.col_type, SqlType::Clob | SqlType::NClob | SqlType::Blob | SqlType::BFile)
+    }
 }
 
 #[derive(Debug)]
@@ -117,6 +213,114 @@ pub struct IndexColumn {
     pub desc: bool
 }
 
+/// Referential constraint, referenced table may be in other (or excluded) schema
+#[derive(Debug)]
+pub struct ForeignKey {
+    pub name:           String,
+    pub column_indices: Vec<usize>,
+    pub ref_schema:     String,
+    pub ref_table:      String,
+    pub ref_columns:    Vec<String>,
+    pub delete_rule:    DeleteRule
+}
+
+/// Standalone procedure or function, or subprogram of package
+#[derive(Debug)]
+pub struct ProcedureInfo {
+    pub package:   Option<String>,
+    pub name:      String,
+    pub overload:  Option<String>,
+    pub arguments: Vec<ArgumentInfo>,
+    /// return value of function
+    pub result:    Option<ArgumentInfo>,
+    /// some arguments have unsupported types, procedure can not be called
+    pub unsupported: bool
+}
+
+impl PartialEq for ProcedureInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.package == other.package && self.name == other.name && self.overload == other.overload
+    }
+}
+impl Eq for ProcedureInfo {}
+
+#[derive(Debug)]
+pub struct ArgumentInfo {
+    pub name:          String,
+    pub col_type:      SqlType,
+    pub oci_data_type: oracle::TypeDescriptor,
+    pub col_type_name: &'static str, // type name in typescript
+    pub direction:     ArgumentDirection,
+    pub defaulted:     bool
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentDirection {
+    In, Out, InOut
+}
+
+impl ArgumentDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArgumentDirection::In => "in",
+            ArgumentDirection::Out => "out",
+            ArgumentDirection::InOut => "in/out",
+        }
+    }
+
+    pub fn is_input(&self) -> bool {
+        *self != ArgumentDirection::Out
+    }
+
+    pub fn is_output(&self) -> bool {
+        *self != ArgumentDirection::In
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeleteRule {
+    NoAction, Cascade, SetNull
+}
+
+impl DeleteRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeleteRule::NoAction => "no action",
+            DeleteRule::Cascade => "cascade",
+            DeleteRule::SetNull => "set null",
+        }
+    }
+}
+
+impl From<&str> for DeleteRule {
+    fn from(rule: &str) -> Self {
+        match rule {
+            "CASCADE" => DeleteRule::Cascade,
+            "SET NULL" => DeleteRule::SetNull,
+            _ => DeleteRule::NoAction
+        }
+    }
+}
+
+/// timestamp and interval types, type of column contains precision: TIMESTAMP(6) WITH TIME ZONE, INTERVAL DAY(2) TO SECOND(6)
+fn datetime_type(data_type: &str) -> Option<SqlType> {
+    if data_type.starts_with("TIMESTAMP") {
+        if data_type.ends_with("WITH LOCAL TIME ZONE") {
+            Some(SqlType::TimestampLtz)
+        } else if data_type.ends_with("WITH TIME ZONE") {
+            Some(SqlType::TimestampTz)
+        } else {
+            Some(SqlType::Timestamp)
+        }
+    } else if data_type.starts_with("INTERVAL YEAR") {
+        Some(SqlType::IntervalYM)
+    } else if data_type.starts_with("INTERVAL DAY") {
+        Some(SqlType::IntervalDS)
+    } else {
+        None
+    }
+}
+
 impl TryFrom<OraTableColumn> for ColumnInfo {
     type Error = &'static str;
 
@@ -137,23 +341,36 @@ impl TryFrom<OraTableColumn> for ColumnInfo {
                 "LONG" => {
                     (SqlType::Varchar, SqlType::Long.into(), "string")
                 },
+                // binary values are hex or base64 strings
+                "RAW" => {
+                    (SqlType::Raw, ((SqlType::Raw, col_len as usize)).into(), "string")
+                },
+                "LONG RAW" => {
+                    (SqlType::LongRaw, SqlType::LongRaw.into(), "string")
+                },
                 "DATE" => {
                     (SqlType::DateTime, SqlType::DateTime.into(), "string")
                 },
-                /*
+                // LOB columns are not fetched with rows, they are read and written by LOB endpoint
                 "CLOB" => {
-                    // SQLT_CLOB
-                    (SqlType::Clob, 112, 0)
+                    (SqlType::Clob, SqlType::Clob.into(), "string")
+                },
+                "NCLOB" => {
+                    (SqlType::NClob, SqlType::NClob.into(), "string")
                 },
                 "BLOB" => {
-                    // SQLT_BLOB
-                    (SqlType::Blob, 113, 0)
+                    (SqlType::Blob, SqlType::Blob.into(), "binary")
+                },
+                "BFILE" => {
+                    (SqlType::BFile, SqlType::BFile.into(), "binary")
                 },
-                 */
                 "NUMBER" => {
+                    // integers over 18 digits and scaled numbers are exact decimals
                     let (col_type, col_type_name) =
                         if data_scale == 0 {
-                            if data_precision == 0 || data_precision > 7 {
+                            if data_precision > 18 {
+                                (SqlType::Decimal, "number")
+                            } else if data_precision == 0 || data_precision > 7 {
                                 (SqlType::Int64, "integer")
                             } else if data_precision > 4 {
                                 (SqlType::Int32, "integer")
@@ -161,17 +378,76 @@ impl TryFrom<OraTableColumn> for ColumnInfo {
                                 (SqlType::Int16, "integer")
                             }
                         } else {
-                            (SqlType::Float64,"number")
+                            (SqlType::Decimal, "number")
                         };
                     (col_type, col_type.into(), col_type_name)
                 },
-                _ => {
+                t => match datetime_type(t) {
+                    Some(col_type) => (col_type, col_type.into(), "string"),
                     // Unsupported
-                    return Err("Unsupported SQL type")
+                    None => return Err("Unsupported SQL type")
                 }
             }
         };
 
-        Ok( ColumnInfo { name, col_type, oci_data_type, col_type_name, nullable } )
+        Ok( ColumnInfo { name, col_type, oci_data_type, col_type_name, nullable, comment: None } )
+    }
+}
+
+impl TryFrom<&OraProcedureArgument> for ArgumentInfo {
+    type Error = &'static str;
+
+    /// types of arguments have no length and precision, unless they are declared with %TYPE
+    fn try_from(v: &OraProcedureArgument) -> Result<Self, Self::Error> {
+        let name = v.argument_name.to_lowercase();
+
+        let (col_type, oci_data_type, col_type_name) = {
+            match v.data_type.as_str() {
+                "CHAR" | "VARCHAR2" => {
+                    let len = if v.data_length > 0 { v.data_length as usize } else { 4000 };
+                    (SqlType::Varchar, ((SqlType::Varchar, len)).into(), "string")
+                },
+                "RAW" => {
+                    let len = if v.data_length > 0 { v.data_length as usize } else { 2000 };
+                    (SqlType::Raw, ((SqlType::Raw, len)).into(), "string")
+                },
+                "DATE" => {
+                    (SqlType::DateTime, SqlType::DateTime.into(), "string")
+                },
+                "BINARY_INTEGER" | "PLS_INTEGER" => {
+                    (SqlType::Int64, SqlType::Int64.into(), "integer")
+                },
+                "NUMBER" if v.data_scale == 0 && v.data_precision > 0 && v.data_precision <= 18 => {
+                    let col_type = if v.data_precision > 7 {
+                        SqlType::Int64
+                    } else if v.data_precision > 4 {
+                        SqlType::Int32
+                    } else {
+                        SqlType::Int16
+                    };
+                    (col_type, col_type.into(), "integer")
+                },
+                "NUMBER" => {
+                    (SqlType::Decimal, SqlType::Decimal.into(), "number")
+                },
+                "FLOAT" | "BINARY_DOUBLE" | "BINARY_FLOAT" => {
+                    (SqlType::Float64, SqlType::Float64.into(), "number")
+                },
+                t => match datetime_type(t) {
+                    Some(col_type) => (col_type, col_type.into(), "string"),
+                    // Unsupported
+                    None => return Err("Unsupported SQL type")
+                }
+            }
+        };
+
+        let direction = match v.in_out.as_str() {
+            "OUT" => ArgumentDirection::Out,
+            "IN/OUT" => ArgumentDirection::InOut,
+            _ => ArgumentDirection::In
+        };
+        let defaulted = v.defaulted == "Y";
+
+        Ok( ArgumentInfo { name, col_type, oci_data_type, col_type_name, direction, defaulted } )
     }
 }
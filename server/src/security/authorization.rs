@@ -11,7 +11,7 @@ use crate::security::SecurityContext;
 
 pub struct AuthorizationMiddleware<S> {
     service: S,
-    only_developer: bool
+    group:   &'static str
 }
 
 impl<S,B> Service for AuthorizationMiddleware<S>
@@ -35,13 +35,7 @@ impl<S,B> Service for AuthorizationMiddleware<S>
                 let extensions = &req.extensions();
                 let context= extensions.get::<SecurityContext>();
                 match context {
-                    Some(ctx) => {
-                        if self.only_developer {
-                            ctx.groups.contains("DEVELOPER")
-                        } else {
-                            ctx.groups.contains("BASE_ACCESS")
-                        }
-                    },
+                    Some(ctx) => ctx.groups.contains(self.group),
                     None => false
                 }
             };
@@ -60,17 +54,27 @@ impl<S,B> Service for AuthorizationMiddleware<S>
 
 }
 
+/// Authorization by membership in group (role) of user
 #[derive(Clone)]
 pub struct Authorized {
-    only_developer: bool
+    group: &'static str
 }
 
 impl Authorized {
     pub fn all() -> Self {
-        Self { only_developer: false}
+        Self { group: "BASE_ACCESS" }
     }
     pub fn developers() -> Self {
-        Self { only_developer: true}
+        Self { group: "DEVELOPER" }
+    }
+    pub fn exporters() -> Self {
+        Self { group: "DATA_EXPORT" }
+    }
+    pub fn writers() -> Self {
+        Self { group: "DATA_WRITE" }
+    }
+    pub fn callers() -> Self {
+        Self { group: "PROCEDURE_CALL" }
     }
 }
 
@@ -88,6 +92,6 @@ impl <S,B> Transform<S> for Authorized
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthorizationMiddleware { service, only_developer: self.only_developer }))
+        ready(Ok(AuthorizationMiddleware { service, group: self.group }))
     }
 }
\ No newline at end of file
@@ -12,6 +12,11 @@ impl SecurityContext {
     pub fn new(subject: String, groups:  HashSet<String>) -> Self {
         Self { subject, groups }
     }
+
+    /// subject of JWT, name of user
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
 }
 
 pub use identity::IdentityService;
@@ -29,4 +29,22 @@ pub fn get_or_insert_with_condition<T,F,P>(optional: &mut Option<T>, creator: F,
         // variant in the code above.
         None => unsafe { hint::unreachable_unchecked() },
     }
-}
\ No newline at end of file
+}
+
+/// encode bytes as lowercase hex string
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b|format!("{:02x}", b)).collect()
+}
+
+/// decode hex string to bytes
+pub fn hex_decode(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 {
+        return Err("Hex string must have even length".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i+2)
+            .and_then(|b|u8::from_str_radix(b, 16).ok())
+            .ok_or_else(||format!("Invalid hex digits at position {}", i)))
+        .collect()
+}